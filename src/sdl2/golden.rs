@@ -0,0 +1,257 @@
+//! Golden-image comparison for rendering tests.
+//!
+//! Rendering to a `Canvas<Surface>` with the software renderer works without a display, which
+//! makes it possible to test drawing code in headless CI. This module compares the rendered
+//! surface with a reference ("golden") image, allowing small per-channel differences so that
+//! tests stay stable across renderers and SDL versions.
+//!
+//! ```no_run
+//! use sdl2::golden;
+//! use sdl2::surface::Surface;
+//!
+//! # fn render() -> Surface<'static> { unimplemented!() }
+//! let expected = Surface::load_bmp("tests/golden/button.bmp").unwrap();
+//! let actual = render();
+//! let comparison = golden::compare(&expected, &actual, 2).unwrap();
+//! if !comparison.is_match() {
+//!     comparison.diff().save_bmp("button-diff.bmp").unwrap();
+//!     panic!("{} pixels differ (PSNR {:.1} dB)", comparison.mismatched_pixels(), comparison.psnr());
+//! }
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use crate::pixels::PixelFormatEnum;
+use crate::surface::{Surface, SurfaceRef};
+
+/// The color of mismatched pixels in the diff surface.
+const DIFF_MISMATCH: [u8; 4] = [255, 0, 0, 255];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompareError {
+    /// The surfaces do not have the same size, given as `(width, height)`.
+    SizeMismatch { expected: (u32, u32), actual: (u32, u32) },
+    SdlError(String),
+}
+
+impl fmt::Display for CompareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CompareError::*;
+
+        match *self {
+            SizeMismatch { expected, actual } => {
+                write!(f, "Expected a {}x{} surface, got {}x{}.",
+                       expected.0, expected.1, actual.0, actual.1)
+            },
+            SdlError(ref e) => write!(f, "SDL error: {}", e),
+        }
+    }
+}
+
+impl StdError for CompareError {
+    fn description(&self) -> &str {
+        use self::CompareError::*;
+
+        match *self {
+            SizeMismatch { .. } => "surface size mismatch",
+            SdlError(ref e) => e,
+        }
+    }
+}
+
+/// The result of comparing two surfaces with `compare`.
+pub struct Comparison {
+    stats: PixelStats,
+    tolerance: u8,
+    diff: Surface<'static>,
+}
+
+impl Comparison {
+    /// Returns `true` if no pixel differs by more than the tolerance.
+    pub fn is_match(&self) -> bool {
+        self.stats.mismatched_pixels == 0
+    }
+
+    /// The number of pixels with at least one channel differing by more than the tolerance.
+    pub fn mismatched_pixels(&self) -> usize {
+        self.stats.mismatched_pixels
+    }
+
+    /// The number of pixels compared.
+    pub fn total_pixels(&self) -> usize {
+        self.stats.total_pixels
+    }
+
+    /// The largest difference found in any channel of any pixel.
+    pub fn max_channel_difference(&self) -> u8 {
+        self.stats.max_channel_difference
+    }
+
+    /// The tolerance used for the comparison.
+    pub fn tolerance(&self) -> u8 {
+        self.tolerance
+    }
+
+    /// The peak signal-to-noise ratio between the surfaces, in decibels, computed over the red,
+    /// green, blue and alpha channels.
+    ///
+    /// Higher is closer; identical surfaces give `f64::INFINITY`.
+    pub fn psnr(&self) -> f64 {
+        self.stats.psnr()
+    }
+
+    /// A surface of the compared size, showing mismatched pixels in red over a faded gray copy
+    /// of the expected surface.
+    pub fn diff(&self) -> &SurfaceRef {
+        &self.diff
+    }
+
+    pub fn into_diff(self) -> Surface<'static> {
+        self.diff
+    }
+}
+
+impl fmt::Debug for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Comparison")
+            .field("mismatched_pixels", &self.stats.mismatched_pixels)
+            .field("total_pixels", &self.stats.total_pixels)
+            .field("max_channel_difference", &self.stats.max_channel_difference)
+            .field("tolerance", &self.tolerance)
+            .field("psnr", &self.psnr())
+            .finish()
+    }
+}
+
+/// Compares two surfaces of the same size, pixel by pixel.
+///
+/// A pixel mismatches if any of its red, green, blue or alpha channels differs by more than
+/// `tolerance`. The surfaces may use different pixel formats: both are converted to `RGBA32`
+/// first.
+pub fn compare(expected: &SurfaceRef, actual: &SurfaceRef, tolerance: u8)
+               -> Result<Comparison, CompareError> {
+    if expected.size() != actual.size() {
+        return Err(CompareError::SizeMismatch { expected: expected.size(), actual: actual.size() });
+    }
+
+    let expected = expected.convert_format(PixelFormatEnum::RGBA32)
+        .map_err(|e| CompareError::SdlError(e.to_string()))?;
+    let actual = actual.convert_format(PixelFormatEnum::RGBA32)
+        .map_err(|e| CompareError::SdlError(e.to_string()))?;
+    let (width, height) = expected.size();
+    let mut diff = Surface::new(width, height, PixelFormatEnum::RGBA32)
+        .map_err(|e| CompareError::SdlError(e.to_string()))?;
+
+    let layout = Layout {
+        width: width as usize,
+        height: height as usize,
+        pitches: [expected.pitch() as usize, actual.pitch() as usize, diff.pitch() as usize],
+    };
+    let stats = expected.with_lock(|expected| {
+        actual.with_lock(|actual| {
+            diff.with_lock_mut(|diff| compare_rgba(expected, actual, diff, &layout, tolerance))
+        })
+    });
+
+    Ok(Comparison { stats, tolerance, diff })
+}
+
+/// The size of the compared images and the pitches of the expected, actual and diff buffers.
+struct Layout {
+    width: usize,
+    height: usize,
+    pitches: [usize; 3],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PixelStats {
+    mismatched_pixels: usize,
+    total_pixels: usize,
+    max_channel_difference: u8,
+    squared_error: u64,
+}
+
+impl PixelStats {
+    fn psnr(&self) -> f64 {
+        if self.squared_error == 0 {
+            return f64::INFINITY;
+        }
+        let mse = self.squared_error as f64 / (self.total_pixels * 4) as f64;
+        10.0 * (255.0 * 255.0 / mse).log10()
+    }
+}
+
+/// Compares two buffers of RGBA bytes and fills the diff buffer.
+fn compare_rgba(expected: &[u8], actual: &[u8], diff: &mut [u8], layout: &Layout, tolerance: u8)
+                -> PixelStats {
+    let mut stats = PixelStats {
+        mismatched_pixels: 0,
+        total_pixels: layout.width * layout.height,
+        max_channel_difference: 0,
+        squared_error: 0,
+    };
+    let [expected_pitch, actual_pitch, diff_pitch] = layout.pitches;
+
+    for y in 0..layout.height {
+        let expected_row = &expected[y * expected_pitch..][..layout.width * 4];
+        let actual_row = &actual[y * actual_pitch..][..layout.width * 4];
+        let diff_row = &mut diff[y * diff_pitch..][..layout.width * 4];
+
+        for ((e, a), d) in expected_row.chunks(4).zip(actual_row.chunks(4)).zip(diff_row.chunks_mut(4)) {
+            let mut max = 0;
+            for (&e, &a) in e.iter().zip(a) {
+                let difference = e.abs_diff(a);
+                stats.squared_error += u64::from(difference) * u64::from(difference);
+                max = max.max(difference);
+            }
+            stats.max_channel_difference = stats.max_channel_difference.max(max);
+
+            if max > tolerance {
+                stats.mismatched_pixels += 1;
+                d.copy_from_slice(&DIFF_MISMATCH);
+            } else {
+                let gray = ((u32::from(e[0]) * 77 + u32::from(e[1]) * 150 + u32::from(e[2]) * 29) >> 8) as u8;
+                let faded = 192 + gray / 4;
+                d.copy_from_slice(&[faded, faded, faded, 255]);
+            }
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compare_rgba, Layout};
+
+    #[test]
+    fn compare_with_tolerance() {
+        let expected = [10, 20, 30, 255, 0, 0, 0, 255, /* padding */ 9, 9,
+                        0, 0, 0, 255, 255, 255, 255, 255, /* padding */ 9, 9];
+        let actual = [12, 20, 28, 255, 0, 0, 0, 255,
+                      0, 0, 0, 255, 255, 200, 255, 255];
+        let mut diff = [0; 16];
+        let layout = Layout { width: 2, height: 2, pitches: [10, 8, 8] };
+
+        let stats = compare_rgba(&expected, &actual, &mut diff, &layout, 2);
+        assert_eq!(stats.total_pixels, 4);
+        assert_eq!(stats.mismatched_pixels, 1);
+        assert_eq!(stats.max_channel_difference, 55);
+        assert_eq!(stats.squared_error, 4 + 4 + 55 * 55);
+        assert_eq!(&diff[12..], &[255, 0, 0, 255]);
+        assert_eq!(&diff[4..8], &[192, 192, 192, 255]);
+
+        let stats = compare_rgba(&expected, &actual, &mut diff, &layout, 55);
+        assert_eq!(stats.mismatched_pixels, 0);
+        assert!(stats.psnr() > 20.0 && stats.psnr() < 30.0);
+    }
+
+    #[test]
+    fn identical_psnr() {
+        let pixels = [1, 2, 3, 4];
+        let mut diff = [0; 4];
+        let layout = Layout { width: 1, height: 1, pitches: [4, 4, 4] };
+        let stats = compare_rgba(&pixels, &pixels, &mut diff, &layout, 0);
+        assert_eq!(stats.psnr(), f64::INFINITY);
+    }
+}
@@ -63,6 +63,9 @@ pub extern crate sdl2_sys as sys;
 #[cfg(feature = "gfx")]
 extern crate c_vec;
 
+#[cfg(feature = "raw-window-handle")]
+pub extern crate raw_window_handle;
+
 pub use crate::sdl::*;
 
 pub mod clipboard;
@@ -83,6 +86,7 @@ pub mod pixels;
 pub mod video;
 pub mod timer;
 pub mod render;
+pub mod golden;
 pub mod rwops;
 pub mod log;
 mod sdl;
@@ -2,8 +2,16 @@ use num::FromPrimitive;
 use std::mem::transmute;
 use std::convert::TryFrom;
 use crate::sys;
+use libc::c_int;
+use std::error::Error as StdError;
+use std::ffi::CStr;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use std::slice;
 
 use crate::{Error, get_error, get_error_as_error};
+use crate::common::validate_int;
 
 pub struct Palette {
     raw: *mut sys::SDL_Palette
@@ -70,6 +78,91 @@ impl Palette {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the color at `index`, or `None` if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Color> {
+        if index < self.len() {
+            Some(Color::from(unsafe { *(*self.raw).colors.add(index) }))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a copy of the colors of the palette.
+    pub fn colors(&self) -> Vec<Color> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let raw_colors = unsafe { slice::from_raw_parts((*self.raw).colors, self.len()) };
+        raw_colors.iter().map(|&color| Color::from(color)).collect()
+    }
+
+    /// Sets the colors of the palette starting at index `start`, like `SDL_SetPaletteColors`.
+    ///
+    /// Surfaces using the palette see the new colors on their next blit. Errors if the colors
+    /// do not fit in the palette.
+    pub fn set_colors(&mut self, start: usize, colors: &[Color]) -> Result<(), Error> {
+        if start > self.len() || colors.len() > self.len() - start {
+            return Err(Error::SdlError(format!(
+                "{} colors starting at index {} do not fit in a palette of {} colors",
+                colors.len(), start, self.len())));
+        }
+        if colors.is_empty() {
+            return Ok(());
+        }
+
+        let raw_colors: Vec<sys::SDL_Color> = colors.iter().map(|color| color.raw()).collect();
+        let result = unsafe {
+            sys::SDL_SetPaletteColors(self.raw, raw_colors.as_ptr(), start as c_int,
+                                      raw_colors.len() as c_int)
+        };
+
+        if result < 0 {
+            Err(get_error_as_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Rotates the colors in `range` by `steps` places, for color cycling animations.
+    ///
+    /// With a positive `steps`, the color at index `i` moves to index `i + steps`, and the colors
+    /// at the end of the range wrap around to its start. A negative `steps` rotates the other way.
+    ///
+    /// ```no_run
+    /// # use sdl2::pixels::Palette;
+    /// # let mut palette: Palette = unimplemented!();
+    /// // Animate a waterfall drawn with the colors 32 to 47, one step per frame
+    /// palette.cycle(32..48, 1).unwrap();
+    /// ```
+    pub fn cycle(&mut self, range: Range<usize>, steps: i32) -> Result<(), Error> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(Error::SdlError(format!(
+                "{:?} is not a range of a palette of {} colors", range, self.len())));
+        }
+        let mut colors = self.colors();
+        let colors = &mut colors[range.clone()];
+        cycle_colors(colors, steps);
+        self.set_colors(range.start, colors)
+    }
+
+    /// Copies the colors of a palette owned by SDL.
+    unsafe fn copy_of(raw: *mut sys::SDL_Palette) -> Result<Palette, Error> {
+        let colors: Vec<Color> = slice::from_raw_parts((*raw).colors, (*raw).ncolors as usize)
+            .iter()
+            .map(|&color| Color::from(color))
+            .collect();
+        Palette::with_colors(&colors)
+    }
+}
+
+/// Rotates `colors` to the right by `steps` places, wrapping around.
+fn cycle_colors(colors: &mut [Color], steps: i32) {
+    if colors.is_empty() {
+        return;
+    }
+    let shift = i64::from(steps).rem_euclid(colors.len() as i64) as usize;
+    colors.rotate_right(shift);
 }
 
 impl Drop for Palette {
@@ -91,6 +184,16 @@ fn create_palette() {
     assert!(palette.len() == 255);
 }
 
+#[test]
+fn cycle_palette_colors() {
+    let mut colors: Vec<_> = (0..4).map(|u| Color::RGB(u, 0, 0)).collect();
+    cycle_colors(&mut colors, 1);
+    assert_eq!(colors.iter().map(|c| c.r).collect::<Vec<_>>(), [3, 0, 1, 2]);
+    cycle_colors(&mut colors, -6);
+    assert_eq!(colors.iter().map(|c| c.r).collect::<Vec<_>>(), [1, 2, 3, 0]);
+    cycle_colors(&mut [], 3);
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Color {
     pub r: u8,
@@ -135,6 +238,164 @@ impl Color {
         (self.r, self.g, self.b, self.a)
     }
 
+    /// Creates an opaque color from a hue in degrees, and a saturation and value between 0 and
+    /// 1.
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Color {
+        let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let chroma = v * s;
+        Color::from_chroma(h, chroma, v - chroma)
+    }
+
+    /// Returns the hue in degrees (between 0 and 360), and the saturation and value (between 0
+    /// and 1) of the color, ignoring alpha.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, min, max) = self.hue_min_max();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+        (hue, saturation, max)
+    }
+
+    /// Creates an opaque color from a hue in degrees, and a saturation and lightness between 0
+    /// and 1.
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Color {
+        let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        Color::from_chroma(h, chroma, l - chroma / 2.0)
+    }
+
+    /// Returns the hue in degrees (between 0 and 360), and the saturation and lightness
+    /// (between 0 and 1) of the color, ignoring alpha.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, min, max) = self.hue_min_max();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min { 0.0 } else { (max - min) / (1.0 - (2.0 * lightness - 1.0).abs()) };
+        (hue, saturation, lightness)
+    }
+
+    /// Creates a color from its hue, chroma and the value added to every channel.
+    fn from_chroma(h: f32, chroma: f32, m: f32) -> Color {
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Color::RGB(to_channel(r + m), to_channel(g + m), to_channel(b + m))
+    }
+
+    /// Returns the hue in degrees, and the smallest and largest of the red, green and blue
+    /// channels, between 0 and 1.
+    fn hue_min_max(self) -> (f32, f32, f32) {
+        let (r, g, b) = (from_channel(self.r), from_channel(self.g), from_channel(self.b));
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (hue, min, max)
+    }
+
+    /// Parses a color written as `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`, with an optional
+    /// `#`.
+    ///
+    /// ```
+    /// use sdl2::pixels::Color;
+    /// assert_eq!(Color::from_hex("#ff8000"), Ok(Color::RGB(255, 128, 0)));
+    /// assert_eq!(Color::from_hex("f80c"), Ok(Color::RGBA(255, 136, 0, 204)));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Color, String> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let invalid = || format!("Invalid hex color: {:?}", hex);
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        // Expands the short forms, where each digit is repeated
+        let short = |shift: u32| ((value >> shift) & 0xF) as u8 * 0x11;
+        let long = |shift: u32| (value >> shift) as u8;
+        match digits.len() {
+            3 => Ok(Color::RGB(short(8), short(4), short(0))),
+            4 => Ok(Color::RGBA(short(12), short(8), short(4), short(0))),
+            6 => Ok(Color::RGB(long(16), long(8), long(0))),
+            8 => Ok(Color::RGBA(long(24), long(16), long(8), long(0))),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Formats the color as `#RRGGBB`, or `#RRGGBBAA` if it is not opaque.
+    pub fn to_hex(self) -> String {
+        if self.a == 0xff {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// Interpolates linearly between `self` (for `t = 0`) and `other` (for `t = 1`), channel
+    /// by channel. `t` is clamped between 0 and 1.
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+        Color::RGBA(mix(self.r, other.r), mix(self.g, other.g), mix(self.b, other.b),
+                    mix(self.a, other.a))
+    }
+
+    /// Composites `self` over `background`, using the Porter-Duff "over" operator with
+    /// non-premultiplied alpha.
+    pub fn over(self, background: Color) -> Color {
+        let alpha = from_channel(self.a);
+        let background_alpha = from_channel(background.a) * (1.0 - alpha);
+        let out_alpha = alpha + background_alpha;
+        if out_alpha == 0.0 {
+            return Color::RGBA(0, 0, 0, 0);
+        }
+        let mix = |a: u8, b: u8| {
+            to_channel((from_channel(a) * alpha + from_channel(b) * background_alpha) / out_alpha)
+        };
+        Color::RGBA(mix(self.r, background.r), mix(self.g, background.g),
+                    mix(self.b, background.b), to_channel(out_alpha))
+    }
+
+    /// Multiplies the red, green and blue channels by alpha.
+    pub fn premultiply(self) -> Color {
+        let multiply = |c: u8| ((u32::from(c) * u32::from(self.a) + 127) / 255) as u8;
+        Color::RGBA(multiply(self.r), multiply(self.g), multiply(self.b), self.a)
+    }
+
+    /// Divides the red, green and blue channels by alpha, reversing `premultiply` up to
+    /// rounding. A fully transparent color gives transparent black.
+    pub fn unpremultiply(self) -> Color {
+        if self.a == 0 {
+            return Color::RGBA(0, 0, 0, 0);
+        }
+        let a = u32::from(self.a);
+        let divide = |c: u8| ((u32::from(c) * 255 + a / 2) / a).min(255) as u8;
+        Color::RGBA(divide(self.r), divide(self.g), divide(self.b), self.a)
+    }
+
+    /// Converts the sRGB channels to linear light, between 0 and 1, as `(r, g, b, a)`.
+    ///
+    /// Alpha is not gamma encoded, so it is only scaled.
+    pub fn to_linear(self) -> (f32, f32, f32, f32) {
+        (srgb_to_linear(self.r), srgb_to_linear(self.g), srgb_to_linear(self.b),
+         from_channel(self.a))
+    }
+
+    /// Creates a color from channels in linear light, between 0 and 1, encoding them to sRGB.
+    pub fn from_linear(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color::RGBA(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), to_channel(a))
+    }
+
     // Implemented manually and kept private, because reasons
     #[inline]
     fn raw(self) -> sys::SDL_Color {
@@ -142,6 +403,95 @@ impl Color {
     }
 }
 
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parses a hex color, see `Color::from_hex`.
+    fn from_str(s: &str) -> Result<Color, String> {
+        Color::from_hex(s)
+    }
+}
+
+fn from_channel(c: u8) -> f32 {
+    f32::from(c) / 255.0
+}
+
+fn to_channel(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = from_channel(c);
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    to_channel(if c <= 0.003_130_8 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 })
+}
+
+#[test]
+fn color_hsv_hsl() {
+    assert_eq!(Color::from_hsv(0.0, 1.0, 1.0), Color::RGB(255, 0, 0));
+    assert_eq!(Color::from_hsv(120.0, 1.0, 0.5), Color::RGB(0, 128, 0));
+    assert_eq!(Color::from_hsv(-120.0, 1.0, 1.0), Color::RGB(0, 0, 255));
+    assert_eq!(Color::from_hsl(60.0, 1.0, 0.5), Color::RGB(255, 255, 0));
+    assert_eq!(Color::from_hsl(0.0, 0.0, 0.5), Color::RGB(128, 128, 128));
+
+    let color = Color::RGB(30, 144, 255);
+    let (h, s, v) = color.to_hsv();
+    assert_eq!(Color::from_hsv(h, s, v), color);
+    let (h, s, l) = color.to_hsl();
+    assert_eq!(Color::from_hsl(h, s, l), color);
+    assert!((h - 209.6).abs() < 0.1);
+    assert_eq!(Color::RGB(0, 0, 0).to_hsl(), (0.0, 0.0, 0.0));
+    assert_eq!(Color::RGB(255, 255, 255).to_hsv(), (0.0, 0.0, 1.0));
+}
+
+#[test]
+fn color_hex() {
+    assert_eq!(Color::from_hex("#1e90ff"), Ok(Color::RGB(30, 144, 255)));
+    assert_eq!(Color::from_hex("1E90FF80"), Ok(Color::RGBA(30, 144, 255, 128)));
+    assert_eq!(Color::from_hex("#fff"), Ok(Color::RGB(255, 255, 255)));
+    assert_eq!("#0f08".parse(), Ok(Color::RGBA(0, 255, 0, 136)));
+    assert!(Color::from_hex("#12345").is_err());
+    assert!(Color::from_hex("#+12").is_err());
+    assert!(Color::from_hex("").is_err());
+    assert_eq!(Color::RGB(30, 144, 255).to_hex(), "#1e90ff");
+    assert_eq!(Color::RGBA(30, 144, 255, 0).to_hex(), "#1e90ff00");
+}
+
+#[test]
+fn color_blending() {
+    let black = Color::RGB(0, 0, 0);
+    let white = Color::RGB(255, 255, 255);
+    assert_eq!(black.lerp(white, 0.5), Color::RGB(128, 128, 128));
+    assert_eq!(black.lerp(white, 2.0), white);
+
+    assert_eq!(Color::RGBA(255, 0, 0, 128).over(white), Color::RGB(255, 127, 127));
+    assert_eq!(Color::RGBA(255, 0, 0, 0).over(white), white);
+    assert_eq!(Color::RGBA(0, 0, 255, 255).over(Color::RGBA(0, 0, 0, 0)), Color::RGB(0, 0, 255));
+    assert_eq!(Color::RGBA(0, 0, 0, 0).over(Color::RGBA(9, 9, 9, 0)), Color::RGBA(0, 0, 0, 0));
+
+    let color = Color::RGBA(200, 100, 50, 128);
+    assert_eq!(color.premultiply(), Color::RGBA(100, 50, 25, 128));
+    assert_eq!(color.premultiply().unpremultiply(), Color::RGBA(199, 100, 50, 128));
+    assert_eq!(Color::RGBA(9, 9, 9, 0).unpremultiply(), Color::RGBA(0, 0, 0, 0));
+}
+
+#[test]
+fn color_linear() {
+    let (r, g, b, a) = Color::RGBA(255, 188, 0, 51).to_linear();
+    assert_eq!((r, b, a), (1.0, 0.0, 0.2));
+    assert!((g - 0.5).abs() < 0.01);
+    assert_eq!(Color::from_linear(1.0, 0.5, 0.0, 0.2), Color::RGBA(255, 188, 0, 51));
+    for c in 0..=255 {
+        let color = Color::RGB(c, c, c);
+        let (r, g, b, a) = color.to_linear();
+        assert_eq!(Color::from_linear(r, g, b, a), color);
+    }
+}
+
 impl Into<sys::SDL_Color> for Color {
     fn into(self) -> sys::SDL_Color {
         self.raw()
@@ -167,6 +517,7 @@ impl From<(u8, u8, u8, u8)> for Color {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct PixelMasks {
     /// Bits per pixel; usually 15, 16, or 32
     pub bpp: u8,
@@ -187,6 +538,33 @@ pub struct PixelFormat {
 impl_raw_accessors!((PixelFormat, *mut sys::SDL_PixelFormat));
 impl_raw_constructor!((PixelFormat, PixelFormat (raw: *mut sys::SDL_PixelFormat)));
 
+impl PixelFormat {
+    /// Returns a copy of the palette of an indexed format, or `None` for the other formats.
+    pub fn palette(&self) -> Option<Palette> {
+        unsafe {
+            let palette = (*self.raw).palette;
+            if palette.is_null() {
+                None
+            } else {
+                Palette::copy_of(palette).ok()
+            }
+        }
+    }
+
+    /// Sets the palette of an indexed format, like `SDL_SetPixelFormatPalette`.
+    ///
+    /// The palette is shared, not copied: changing its colors later changes the colors of the
+    /// format.
+    pub fn set_palette(&mut self, palette: &Palette) -> Result<(), Error> {
+        let result = unsafe { sys::SDL_SetPixelFormatPalette(self.raw, palette.raw) };
+
+        match result {
+            0 => Ok(()),
+            _ => Err(get_error_as_error())
+        }
+    }
+}
+
 #[repr(i32)]
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum PixelFormatEnum {
@@ -225,7 +603,9 @@ pub enum PixelFormatEnum {
     IYUV = sys::SDL_PIXELFORMAT_IYUV as i32,
     YUY2 = sys::SDL_PIXELFORMAT_YUY2 as i32,
     UYVY = sys::SDL_PIXELFORMAT_UYVY as i32,
-    YVYU = sys::SDL_PIXELFORMAT_YVYU as i32
+    YVYU = sys::SDL_PIXELFORMAT_YVYU as i32,
+    NV12 = sys::SDL_PIXELFORMAT_NV12 as i32,
+    NV21 = sys::SDL_PIXELFORMAT_NV21 as i32
 }
 
 // Endianness-agnostic aliases for 32-bit formats
@@ -281,12 +661,14 @@ impl PixelFormatEnum {
     /// and height.
     pub fn byte_size_from_pitch_and_height(self, pitch: usize, height: usize) -> usize {
         match self {
-            PixelFormatEnum::YV12 | PixelFormatEnum::IYUV => {
+            PixelFormatEnum::YV12 | PixelFormatEnum::IYUV |
+            PixelFormatEnum::NV12 | PixelFormatEnum::NV21 => {
                 // YUV is 4:2:0.
                 // `pitch` is the width of the Y component, and
                 // `height` is the height of the Y component.
-                // U and V have half the width and height of Y.
-                pitch * height + 2 * (pitch / 2 * height / 2)
+                // U and V have half the width and height of Y, rounded up.
+                // NV12 and NV21 interleave U and V in a single plane of the same size.
+                pitch * height + 2 * pitch.div_ceil(2) * height.div_ceil(2)
             },
             _ => pitch * height
         }
@@ -315,7 +697,8 @@ impl PixelFormatEnum {
                 => num_of_pixels * 4,
             // YUV formats
             // FIXME: rounding error here?
-            PixelFormatEnum::YV12 | PixelFormatEnum::IYUV
+            PixelFormatEnum::YV12 | PixelFormatEnum::IYUV |
+            PixelFormatEnum::NV12 | PixelFormatEnum::NV21
                 => num_of_pixels / 2 * 3,
             PixelFormatEnum::YUY2 | PixelFormatEnum::UYVY |
             PixelFormatEnum::YVYU
@@ -352,7 +735,8 @@ impl PixelFormatEnum {
             PixelFormatEnum::ARGB2101010
                 => 4,
             // YUV formats
-            PixelFormatEnum::YV12 | PixelFormatEnum::IYUV
+            PixelFormatEnum::YV12 | PixelFormatEnum::IYUV |
+            PixelFormatEnum::NV12 | PixelFormatEnum::NV21
                 => 2,
             PixelFormatEnum::YUY2 | PixelFormatEnum::UYVY |
             PixelFormatEnum::YVYU
@@ -377,6 +761,297 @@ impl PixelFormatEnum {
             _ => false
         }
     }
+
+    /// The name of the format, like `SDL_GetPixelFormatName`, for example
+    /// `"SDL_PIXELFORMAT_RGB24"`.
+    pub fn name(self) -> &'static str {
+        unsafe {
+            CStr::from_ptr(sys::SDL_GetPixelFormatName(self as u32)).to_str().unwrap()
+        }
+    }
+
+    /// Returns `true` for the formats whose pixels are defined by a FourCC code, which are the
+    /// YUV formats.
+    pub fn is_fourcc(self) -> bool {
+        let format = self as u32;
+        format != 0 && (format >> 28) & 0x0F != 1
+    }
+
+    /// Returns `true` for the formats whose pixels are indices in a palette.
+    pub fn is_indexed(self) -> bool {
+        let pixel_type = self.info().map(|info| info.pixel_type);
+        pixel_type == Some(PixelType::Index1) || pixel_type == Some(PixelType::Index4) ||
+            pixel_type == Some(PixelType::Index8)
+    }
+
+    /// Decomposes the format into its pixel type, channel order, packed layout and sizes.
+    ///
+    /// Returns `None` for `Unknown` and the FourCC formats, which don't have this layout.
+    ///
+    /// ```
+    /// use sdl2::pixels::{ChannelOrder, PackedLayout, PixelFormatEnum, PixelType};
+    ///
+    /// let info = PixelFormatEnum::RGB565.info().unwrap();
+    /// assert_eq!(info.pixel_type, PixelType::Packed16);
+    /// assert_eq!(info.order, ChannelOrder::XRGB);
+    /// assert_eq!(info.layout, PackedLayout::Layout565);
+    /// assert_eq!(info.channel_bits(), (5, 6, 5, 0));
+    /// ```
+    pub fn info(self) -> Option<PixelFormatInfo> {
+        if self == PixelFormatEnum::Unknown || self.is_fourcc() {
+            return None;
+        }
+        let format = self as u32;
+        let pixel_type = PixelType::from_ll((format >> 24) & 0x0F);
+        let order = ChannelOrder::from_ll(pixel_type, (format >> 20) & 0x0F);
+        Some(PixelFormatInfo {
+            pixel_type,
+            order,
+            layout: PackedLayout::from_ll((format >> 16) & 0x0F),
+            bits_per_pixel: (format >> 8) as u8,
+            bytes_per_pixel: format as u8,
+        })
+    }
+
+    /// The pixel masks of the format, computed without calling SDL.
+    ///
+    /// Returns `None` for the formats without masks: `Unknown`, the indexed formats and the
+    /// FourCC formats.
+    pub fn masks(self) -> Option<PixelMasks> {
+        let info = self.info()?;
+        let bpp = info.bits_per_pixel;
+        match info.pixel_type {
+            PixelType::Packed8 | PixelType::Packed16 | PixelType::Packed32 => {
+                let masks = info.layout.masks();
+                let (r, g, b, a) = match info.order {
+                    ChannelOrder::XRGB => (masks[1], masks[2], masks[3], 0),
+                    ChannelOrder::RGBX => (masks[0], masks[1], masks[2], 0),
+                    ChannelOrder::ARGB => (masks[1], masks[2], masks[3], masks[0]),
+                    ChannelOrder::RGBA => (masks[0], masks[1], masks[2], masks[3]),
+                    ChannelOrder::XBGR => (masks[3], masks[2], masks[1], 0),
+                    ChannelOrder::BGRX => (masks[2], masks[1], masks[0], 0),
+                    ChannelOrder::ABGR => (masks[3], masks[2], masks[1], masks[0]),
+                    ChannelOrder::BGRA => (masks[2], masks[1], masks[0], masks[3]),
+                    _ => return None,
+                };
+                Some(PixelMasks { bpp, rmask: r, gmask: g, bmask: b, amask: a })
+            },
+            // The bytes of the 24-bit formats are in memory order, like SDL_PixelFormatEnumToMasks
+            PixelType::ArrayU8 if bpp == 24 => {
+                let (first, last) = if cfg!(target_endian = "big") {
+                    (0x00FF_0000, 0x0000_00FF)
+                } else {
+                    (0x0000_00FF, 0x00FF_0000)
+                };
+                let (rmask, bmask) = match info.order {
+                    ChannelOrder::RGB => (first, last),
+                    ChannelOrder::BGR => (last, first),
+                    _ => return None,
+                };
+                Some(PixelMasks { bpp, rmask, gmask: 0x0000_FF00, bmask, amask: 0 })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The decomposition of a `PixelFormatEnum`, given by `PixelFormatEnum::info`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PixelFormatInfo {
+    pub pixel_type: PixelType,
+    /// The order of the channels, from the most significant bits for packed formats, and from
+    /// the first byte for array formats.
+    pub order: ChannelOrder,
+    /// The number of bits of each channel, for packed formats.
+    pub layout: PackedLayout,
+    /// The number of significant bits of a pixel, for example 15 for `RGB555`.
+    pub bits_per_pixel: u8,
+    /// The number of bytes used to store a pixel, 0 for the formats with several pixels per
+    /// byte.
+    pub bytes_per_pixel: u8,
+}
+
+impl PixelFormatInfo {
+    /// The number of bits of the red, green, blue and alpha channels, as `(r, g, b, a)`.
+    ///
+    /// All are 0 for the indexed formats.
+    pub fn channel_bits(&self) -> (u8, u8, u8, u8) {
+        let channel_bits = match self.pixel_type {
+            PixelType::ArrayU8 => 8,
+            PixelType::ArrayU16 | PixelType::ArrayF16 => 16,
+            PixelType::ArrayU32 | PixelType::ArrayF32 => 32,
+            PixelType::Packed8 | PixelType::Packed16 | PixelType::Packed32 => {
+                let bits = self.layout.masks();
+                let count = |i: usize| bits[i].count_ones() as u8;
+                return match self.order {
+                    ChannelOrder::XRGB => (count(1), count(2), count(3), 0),
+                    ChannelOrder::RGBX => (count(0), count(1), count(2), 0),
+                    ChannelOrder::ARGB => (count(1), count(2), count(3), count(0)),
+                    ChannelOrder::RGBA => (count(0), count(1), count(2), count(3)),
+                    ChannelOrder::XBGR => (count(3), count(2), count(1), 0),
+                    ChannelOrder::BGRX => (count(2), count(1), count(0), 0),
+                    ChannelOrder::ABGR => (count(3), count(2), count(1), count(0)),
+                    ChannelOrder::BGRA => (count(2), count(1), count(0), count(3)),
+                    _ => (0, 0, 0, 0),
+                };
+            },
+            _ => return (0, 0, 0, 0),
+        };
+        let has_alpha = self.order == ChannelOrder::RGBA || self.order == ChannelOrder::ARGB ||
+            self.order == ChannelOrder::BGRA || self.order == ChannelOrder::ABGR;
+        (channel_bits, channel_bits, channel_bits, if has_alpha { channel_bits } else { 0 })
+    }
+}
+
+/// How the pixels of a format are stored, from `SDL_PixelType`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PixelType {
+    Unknown,
+    /// Indices of 1 bit, 8 pixels per byte.
+    Index1,
+    /// Indices of 4 bits, 2 pixels per byte.
+    Index4,
+    Index8,
+    /// Channels packed in an 8-bit value.
+    Packed8,
+    /// Channels packed in a 16-bit value, in native byte order.
+    Packed16,
+    /// Channels packed in a 32-bit value, in native byte order.
+    Packed32,
+    /// One byte per channel, in memory order.
+    ArrayU8,
+    ArrayU16,
+    ArrayU32,
+    ArrayF16,
+    ArrayF32,
+}
+
+// The values of the fields of the pixel format enum are the ones of `SDL_PixelType`,
+// `SDL_BitmapOrder`, `SDL_PackedOrder`, `SDL_ArrayOrder` and `SDL_PackedLayout`.
+
+impl PixelType {
+    fn from_ll(value: u32) -> PixelType {
+        match value {
+            1 => PixelType::Index1,
+            2 => PixelType::Index4,
+            3 => PixelType::Index8,
+            4 => PixelType::Packed8,
+            5 => PixelType::Packed16,
+            6 => PixelType::Packed32,
+            7 => PixelType::ArrayU8,
+            8 => PixelType::ArrayU16,
+            9 => PixelType::ArrayU32,
+            10 => PixelType::ArrayF16,
+            11 => PixelType::ArrayF32,
+            _ => PixelType::Unknown,
+        }
+    }
+}
+
+/// The order of the channels of a format, combining SDL's bitmap, packed and array orders.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ChannelOrder {
+    None,
+    /// Indexed formats with the first pixel in the low-order bits of a byte.
+    Bitmap4321,
+    /// Indexed formats with the first pixel in the high-order bits of a byte.
+    Bitmap1234,
+    XRGB,
+    RGBX,
+    ARGB,
+    RGBA,
+    XBGR,
+    BGRX,
+    ABGR,
+    BGRA,
+    RGB,
+    BGR,
+}
+
+impl ChannelOrder {
+    fn from_ll(pixel_type: PixelType, value: u32) -> ChannelOrder {
+        use self::ChannelOrder::*;
+
+        match pixel_type {
+            PixelType::Index1 | PixelType::Index4 | PixelType::Index8 => match value {
+                1 => Bitmap4321,
+                2 => Bitmap1234,
+                _ => None,
+            },
+            PixelType::Packed8 | PixelType::Packed16 | PixelType::Packed32 => match value {
+                1 => XRGB,
+                2 => RGBX,
+                3 => ARGB,
+                4 => RGBA,
+                5 => XBGR,
+                6 => BGRX,
+                7 => ABGR,
+                8 => BGRA,
+                _ => None,
+            },
+            PixelType::ArrayU8 | PixelType::ArrayU16 | PixelType::ArrayU32 |
+            PixelType::ArrayF16 | PixelType::ArrayF32 => match value {
+                1 => RGB,
+                2 => RGBA,
+                3 => ARGB,
+                4 => BGR,
+                5 => BGRA,
+                6 => ABGR,
+                _ => None,
+            },
+            PixelType::Unknown => None,
+        }
+    }
+}
+
+/// The number of bits of the channels of a packed format, from the most significant bits.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PackedLayout {
+    None,
+    Layout332,
+    Layout4444,
+    Layout1555,
+    Layout5551,
+    Layout565,
+    Layout8888,
+    Layout2101010,
+    Layout1010102,
+}
+
+impl PackedLayout {
+    fn from_ll(value: u32) -> PackedLayout {
+        use self::PackedLayout::*;
+
+        match value {
+            1 => Layout332,
+            2 => Layout4444,
+            3 => Layout1555,
+            4 => Layout5551,
+            5 => Layout565,
+            6 => Layout8888,
+            7 => Layout2101010,
+            8 => Layout1010102,
+            _ => None,
+        }
+    }
+
+    /// The masks of the four components of the layout, from the most significant bits. The
+    /// layouts with three components have an empty first mask.
+    fn masks(self) -> [u32; 4] {
+        use self::PackedLayout::*;
+
+        match self {
+            None => [0, 0, 0, 0],
+            Layout332 => [0, 0xE0, 0x1C, 0x03],
+            Layout4444 => [0xF000, 0x0F00, 0x00F0, 0x000F],
+            Layout1555 => [0x8000, 0x7C00, 0x03E0, 0x001F],
+            Layout5551 => [0xF800, 0x07C0, 0x003E, 0x0001],
+            Layout565 => [0, 0xF800, 0x07E0, 0x001F],
+            Layout8888 => [0xFF00_0000, 0x00FF_0000, 0x0000_FF00, 0x0000_00FF],
+            Layout2101010 => [0xC000_0000, 0x3FF0_0000, 0x000F_FC00, 0x0000_03FF],
+            Layout1010102 => [0xFFC0_0000, 0x003F_F000, 0x0000_0FFC, 0x0000_0003],
+        }
+    }
 }
 
 impl From<PixelFormat> for PixelFormatEnum {
@@ -433,6 +1108,8 @@ impl FromPrimitive for PixelFormatEnum {
             sys::SDL_PIXELFORMAT_YUY2        => YUY2,
             sys::SDL_PIXELFORMAT_UYVY        => UYVY,
             sys::SDL_PIXELFORMAT_YVYU        => YVYU,
+            sys::SDL_PIXELFORMAT_NV12        => NV12,
+            sys::SDL_PIXELFORMAT_NV21        => NV21,
             _                               => return None,
         })
     }
@@ -456,6 +1133,280 @@ impl TryFrom<PixelFormatEnum> for PixelFormat {
 }
 
 
+/// The YUV conversion mode used by SDL to convert between YUV and RGB formats, for example
+/// when rendering YUV textures with the software renderer.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum YuvConversionMode {
+    /// Full range JPEG.
+    Jpeg,
+    /// BT.601, the standard for SD video.
+    Bt601,
+    /// BT.709, the standard for HD video.
+    Bt709,
+    /// BT.601 for SD content and BT.709 for HD content, chosen from the resolution.
+    Automatic,
+}
+
+impl YuvConversionMode {
+    pub fn from_ll(mode: sys::SDL_YUV_CONVERSION_MODE) -> YuvConversionMode {
+        use crate::sys::SDL_YUV_CONVERSION_MODE::*;
+        match mode {
+            SDL_YUV_CONVERSION_JPEG => YuvConversionMode::Jpeg,
+            SDL_YUV_CONVERSION_BT601 => YuvConversionMode::Bt601,
+            SDL_YUV_CONVERSION_BT709 => YuvConversionMode::Bt709,
+            SDL_YUV_CONVERSION_AUTOMATIC => YuvConversionMode::Automatic,
+        }
+    }
+
+    pub fn to_ll(self) -> sys::SDL_YUV_CONVERSION_MODE {
+        use crate::sys::SDL_YUV_CONVERSION_MODE::*;
+        match self {
+            YuvConversionMode::Jpeg => SDL_YUV_CONVERSION_JPEG,
+            YuvConversionMode::Bt601 => SDL_YUV_CONVERSION_BT601,
+            YuvConversionMode::Bt709 => SDL_YUV_CONVERSION_BT709,
+            YuvConversionMode::Automatic => SDL_YUV_CONVERSION_AUTOMATIC,
+        }
+    }
+}
+
+/// Sets the YUV conversion mode, for all the YUV conversions done by SDL.
+pub fn set_yuv_conversion_mode(mode: YuvConversionMode) {
+    unsafe { sys::SDL_SetYUVConversionMode(mode.to_ll()) }
+}
+
+/// Gets the YUV conversion mode, which may be `YuvConversionMode::Automatic`.
+pub fn yuv_conversion_mode() -> YuvConversionMode {
+    YuvConversionMode::from_ll(unsafe { sys::SDL_GetYUVConversionMode() })
+}
+
+/// Gets the YUV conversion mode used for content of the given resolution, which is never
+/// `YuvConversionMode::Automatic`.
+pub fn yuv_conversion_mode_for_resolution(width: u32, height: u32) -> YuvConversionMode {
+    let mode = unsafe {
+        sys::SDL_GetYUVConversionModeForResolution(width as c_int, height as c_int)
+    };
+    YuvConversionMode::from_ll(mode)
+}
+
+#[test]
+fn pixel_format_info() {
+    let info = PixelFormatEnum::ARGB2101010.info().unwrap();
+    assert_eq!(info, PixelFormatInfo {
+        pixel_type: PixelType::Packed32,
+        order: ChannelOrder::ARGB,
+        layout: PackedLayout::Layout2101010,
+        bits_per_pixel: 32,
+        bytes_per_pixel: 4,
+    });
+    assert_eq!(info.channel_bits(), (10, 10, 10, 2));
+    assert_eq!(PixelFormatEnum::RGB555.info().unwrap().bits_per_pixel, 15);
+    assert_eq!(PixelFormatEnum::BGR24.info().unwrap().order, ChannelOrder::BGR);
+    assert_eq!(PixelFormatEnum::BGR24.info().unwrap().channel_bits(), (8, 8, 8, 0));
+    assert_eq!(PixelFormatEnum::Index1MSB.info().unwrap().order, ChannelOrder::Bitmap1234);
+    assert_eq!(PixelFormatEnum::Index4LSB.info().unwrap().bytes_per_pixel, 0);
+    assert_eq!(PixelFormatEnum::YUY2.info(), None);
+    assert_eq!(PixelFormatEnum::Unknown.info(), None);
+
+    assert!(PixelFormatEnum::Index8.is_indexed());
+    assert!(!PixelFormatEnum::RGB332.is_indexed());
+    assert!(!PixelFormatEnum::NV12.is_indexed());
+    assert!(PixelFormatEnum::NV12.is_fourcc());
+
+    assert_eq!(PixelFormatEnum::BGRA4444.masks(), Some(PixelMasks {
+        bpp: 16, rmask: 0x00F0, gmask: 0x0F00, bmask: 0xF000, amask: 0x000F,
+    }));
+    assert_eq!(PixelFormatEnum::RGB332.masks(), Some(PixelMasks {
+        bpp: 8, rmask: 0xE0, gmask: 0x1C, bmask: 0x03, amask: 0,
+    }));
+    assert_eq!(PixelFormatEnum::Index8.masks(), None);
+    assert_eq!(PixelFormatEnum::IYUV.masks(), None);
+}
+
+#[test]
+fn yuv_conversion_mode_round_trip() {
+    for &mode in &[YuvConversionMode::Jpeg, YuvConversionMode::Bt601,
+                   YuvConversionMode::Bt709, YuvConversionMode::Automatic] {
+        assert_eq!(YuvConversionMode::from_ll(mode.to_ll()), mode);
+    }
+}
+
+#[test]
+fn yuv_byte_size() {
+    assert_eq!(PixelFormatEnum::IYUV.byte_size_from_pitch_and_height(4, 2), 12);
+    // odd sizes round the chroma planes up, like SDL does
+    assert_eq!(PixelFormatEnum::IYUV.byte_size_from_pitch_and_height(3, 3), 17);
+    assert_eq!(PixelFormatEnum::NV12.byte_size_from_pitch_and_height(5, 3), 27);
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvertPixelsError {
+    WidthOverflows(u32),
+    HeightOverflows(u32),
+    PitchOverflows { buffer: &'static str, value: usize },
+    /// The pitch of a buffer is smaller than a row of pixels.
+    PitchTooSmall { buffer: &'static str, pitch: usize, min_pitch: usize },
+    /// A buffer is smaller than `pitch * height` bytes (including the chroma planes for planar
+    /// YUV formats).
+    BufferTooSmall { buffer: &'static str, length: usize, min_length: usize },
+    UnsupportedFormat(PixelFormatEnum),
+    SdlError(String),
+}
+
+impl fmt::Display for ConvertPixelsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::ConvertPixelsError::*;
+
+        match *self {
+            WidthOverflows(value) => write!(f, "Width overflows ({})", value),
+            HeightOverflows(value) => write!(f, "Height overflows ({})", value),
+            PitchOverflows { buffer, value } => {
+                write!(f, "Pitch overflows on {} buffer ({})", buffer, value)
+            },
+            PitchTooSmall { buffer, pitch, min_pitch } => {
+                write!(f, "The {} pitch is too small ({}, should be at least {})",
+                       buffer, pitch, min_pitch)
+            },
+            BufferTooSmall { buffer, length, min_length } => {
+                write!(f, "The {} buffer is too small ({}, should be at least {})",
+                       buffer, length, min_length)
+            },
+            UnsupportedFormat(format) => {
+                write!(f, "Pixel format '{:?}' is not supported for conversion", format)
+            },
+            SdlError(ref e) => write!(f, "SDL error: {}", e),
+        }
+    }
+}
+
+impl StdError for ConvertPixelsError {
+    fn description(&self) -> &str {
+        use self::ConvertPixelsError::*;
+
+        match *self {
+            WidthOverflows(_) => "width overflow",
+            HeightOverflows(_) => "height overflow",
+            PitchOverflows { .. } => "pitch overflow",
+            PitchTooSmall { .. } => "pitch too small",
+            BufferTooSmall { .. } => "buffer too small",
+            UnsupportedFormat(_) => "unsupported pixel format",
+            SdlError(ref e) => e,
+        }
+    }
+}
+
+/// Returns the smallest pitch of a buffer of `width` pixels, or `None` for the formats with
+/// pixels smaller than a byte.
+///
+/// `width` must be smaller than `1 << 31`.
+fn min_pitch(format: PixelFormatEnum, width: usize) -> Option<usize> {
+    match format {
+        PixelFormatEnum::Unknown | PixelFormatEnum::Index1LSB | PixelFormatEnum::Index1MSB |
+        PixelFormatEnum::Index4LSB | PixelFormatEnum::Index4MSB => None,
+        // The pitch of planar formats is the pitch of the Y plane
+        PixelFormatEnum::YV12 | PixelFormatEnum::IYUV |
+        PixelFormatEnum::NV12 | PixelFormatEnum::NV21 => Some(width),
+        // Packed 4:2:2 formats store pairs of pixels in 4 bytes
+        PixelFormatEnum::YUY2 | PixelFormatEnum::UYVY |
+        PixelFormatEnum::YVYU => Some(width.div_ceil(2) * 4),
+        _ => Some(format.byte_size_of_pixels(width)),
+    }
+}
+
+/// Checks that a buffer can hold `height` rows of `width` pixels with the given pitch.
+fn check_pixel_buffer(buffer: &'static str, format: PixelFormatEnum, width: usize, height: usize,
+                      pitch: usize, length: usize) -> Result<c_int, ConvertPixelsError> {
+    use self::ConvertPixelsError::*;
+
+    if pitch >= 1 << 31 {
+        return Err(PitchOverflows { buffer, value: pitch });
+    }
+    let min_pitch = min_pitch(format, width).ok_or(UnsupportedFormat(format))?;
+    if pitch < min_pitch {
+        return Err(PitchTooSmall { buffer, pitch, min_pitch });
+    }
+    let min_length = format.byte_size_from_pitch_and_height(pitch, height);
+    if length < min_length {
+        return Err(BufferTooSmall { buffer, length, min_length });
+    }
+    Ok(pitch as c_int)
+}
+
+/// Converts a block of pixels from one format to another.
+///
+/// `src` and `dst` must hold `height` rows of `width` pixels, each row starting `src_pitch` (or
+/// `dst_pitch`) bytes after the previous one. For planar YUV formats, the pitch is the pitch of
+/// the Y plane, and the chroma planes follow it in the buffer.
+pub fn convert_pixels(width: u32, height: u32,
+                      src_format: PixelFormatEnum, src: &[u8], src_pitch: usize,
+                      dst_format: PixelFormatEnum, dst: &mut [u8], dst_pitch: usize)
+                      -> Result<(), ConvertPixelsError> {
+    use self::ConvertPixelsError::*;
+
+    let c_width = validate_int(width, "width").map_err(|_| WidthOverflows(width))?;
+    let c_height = validate_int(height, "height").map_err(|_| HeightOverflows(height))?;
+    let (width, height) = (width as usize, height as usize);
+    let src_pitch = check_pixel_buffer("source", src_format, width, height, src_pitch, src.len())?;
+    let dst_pitch = check_pixel_buffer("destination", dst_format, width, height, dst_pitch,
+                                       dst.len())?;
+
+    let ret = unsafe {
+        sys::SDL_ConvertPixels(c_width, c_height,
+                               src_format as u32, src.as_ptr() as *const _, src_pitch,
+                               dst_format as u32, dst.as_mut_ptr() as *mut _, dst_pitch)
+    };
+    if ret != 0 { Err(SdlError(get_error())) } else { Ok(()) }
+}
+
+/// Converts a block of pixels from one format to another, like `convert_pixels`, into a new
+/// buffer without padding between rows.
+///
+/// Returns the converted pixels and their pitch.
+pub fn convert_pixels_to_vec(width: u32, height: u32,
+                             src_format: PixelFormatEnum, src: &[u8], src_pitch: usize,
+                             dst_format: PixelFormatEnum)
+                             -> Result<(Vec<u8>, usize), ConvertPixelsError> {
+    use self::ConvertPixelsError::*;
+
+    validate_int(width, "width").map_err(|_| WidthOverflows(width))?;
+    validate_int(height, "height").map_err(|_| HeightOverflows(height))?;
+    let dst_pitch = min_pitch(dst_format, width as usize).ok_or(UnsupportedFormat(dst_format))?;
+    let mut dst = vec![0; dst_format.byte_size_from_pitch_and_height(dst_pitch, height as usize)];
+    convert_pixels(width, height, src_format, src, src_pitch, dst_format, &mut dst, dst_pitch)?;
+    Ok((dst, dst_pitch))
+}
+
+#[test]
+fn convert_pixels_validation() {
+    use self::ConvertPixelsError::*;
+
+    assert!(check_pixel_buffer("source", PixelFormatEnum::RGB24, 3, 2, 12, 24).is_ok());
+    assert_eq!(check_pixel_buffer("source", PixelFormatEnum::RGB24, 3, 2, 8, 24),
+               Err(PitchTooSmall { buffer: "source", pitch: 8, min_pitch: 9 }));
+    assert_eq!(check_pixel_buffer("destination", PixelFormatEnum::ARGB8888, 3, 2, 12, 20),
+               Err(BufferTooSmall { buffer: "destination", length: 20, min_length: 24 }));
+    // the chroma planes of 4:2:0 formats add half of the Y plane
+    assert_eq!(check_pixel_buffer("source", PixelFormatEnum::NV12, 4, 4, 4, 16),
+               Err(BufferTooSmall { buffer: "source", length: 16, min_length: 24 }));
+    // odd sizes round the chroma planes up
+    assert!(check_pixel_buffer("source", PixelFormatEnum::IYUV, 3, 3, 3, 17).is_ok());
+    assert_eq!(check_pixel_buffer("source", PixelFormatEnum::IYUV, 3, 3, 3, 11),
+               Err(BufferTooSmall { buffer: "source", length: 11, min_length: 17 }));
+    assert_eq!(check_pixel_buffer("source", PixelFormatEnum::NV21, 3, 3, 3, 15),
+               Err(BufferTooSmall { buffer: "source", length: 15, min_length: 17 }));
+    // packed 4:2:2 rows hold whole pairs of pixels
+    assert!(check_pixel_buffer("source", PixelFormatEnum::YUY2, 3, 1, 8, 8).is_ok());
+    assert_eq!(check_pixel_buffer("source", PixelFormatEnum::UYVY, 3, 1, 6, 8),
+               Err(PitchTooSmall { buffer: "source", pitch: 6, min_pitch: 8 }));
+    assert_eq!(check_pixel_buffer("source", PixelFormatEnum::Index1MSB, 8, 1, 1, 1),
+               Err(UnsupportedFormat(PixelFormatEnum::Index1MSB)));
+    // the pitch is checked before computing the buffer size from it
+    assert_eq!(check_pixel_buffer("source", PixelFormatEnum::Index8, 8, 1 << 30, 1 << 31, 0),
+               Err(PitchOverflows { buffer: "source", value: 1 << 31 }));
+    assert_eq!(min_pitch(PixelFormatEnum::YVYU, 5), Some(12));
+}
+
+
 // Just test a round-trip conversion from PixelFormat to
 // PixelFormatEnum and back.
 #[test]
@@ -478,6 +1429,7 @@ fn test_pixel_format_enum() {
         PixelFormatEnum::YV12, PixelFormatEnum::IYUV,
         PixelFormatEnum::YUY2, PixelFormatEnum::UYVY,
         PixelFormatEnum::YVYU,
+        PixelFormatEnum::NV12, PixelFormatEnum::NV21,
         PixelFormatEnum::Index8,
         // These don't seem to be supported;
         // the round-trip
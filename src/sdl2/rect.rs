@@ -885,9 +885,513 @@ impl DivAssign<i32> for Point {
     }
 }
 
+/// How coordinates are rounded when converting an `FPoint` or an `FRect` to integers.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RoundingMode {
+    /// Rounds to the nearest integer, and half-way cases away from zero.
+    Nearest,
+    /// Rounds towards negative infinity.
+    Floor,
+    /// Rounds towards positive infinity.
+    Ceil,
+    /// Rounds towards zero.
+    Truncate,
+}
+
+impl RoundingMode {
+    fn round(self, value: f32) -> i32 {
+        let rounded = match self {
+            RoundingMode::Nearest => value.round(),
+            RoundingMode::Floor => value.floor(),
+            RoundingMode::Ceil => value.ceil(),
+            RoundingMode::Truncate => value.trunc(),
+        };
+        // Saturates, and NaN gives 0
+        clamp_position(rounded as i32)
+    }
+}
+
+/// A point with floating point coordinates.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FPoint {
+    x: f32,
+    y: f32,
+}
+
+impl FPoint {
+    /// Creates a new point from the given coordinates.
+    pub fn new(x: f32, y: f32) -> FPoint {
+        FPoint { x, y }
+    }
+
+    /// Returns the x-coordinate of this point.
+    pub fn x(self) -> f32 {
+        self.x
+    }
+
+    /// Returns the y-coordinate of this point.
+    pub fn y(self) -> f32 {
+        self.y
+    }
+
+    /// Returns a new point by shifting this point's coordinates by the given
+    /// x and y values.
+    pub fn offset(self, x: f32, y: f32) -> FPoint {
+        FPoint::new(self.x + x, self.y + y)
+    }
+
+    /// Returns a new point by multiplying this point's coordinates by the
+    /// given scale factor.
+    pub fn scale(self, f: f32) -> FPoint {
+        FPoint::new(self.x * f, self.y * f)
+    }
+
+    /// Converts this point to integer coordinates, rounded with `mode`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::rect::{FPoint, Point, RoundingMode};
+    /// let point = FPoint::new(1.5, -1.5);
+    /// assert_eq!(point.to_point(RoundingMode::Nearest), Point::new(2, -2));
+    /// assert_eq!(point.to_point(RoundingMode::Floor), Point::new(1, -2));
+    /// assert_eq!(point.to_point(RoundingMode::Truncate), Point::new(1, -1));
+    /// ```
+    pub fn to_point(self, mode: RoundingMode) -> Point {
+        Point::new(mode.round(self.x), mode.round(self.y))
+    }
+}
+
+impl From<Point> for FPoint {
+    /// Converts the coordinates exactly, for coordinates smaller than 2^24 in absolute value.
+    fn from(point: Point) -> FPoint {
+        FPoint::new(point.x() as f32, point.y() as f32)
+    }
+}
+
+impl From<(f32, f32)> for FPoint {
+    fn from((x, y): (f32, f32)) -> FPoint {
+        FPoint::new(x, y)
+    }
+}
+
+impl From<FPoint> for (f32, f32) {
+    fn from(point: FPoint) -> (f32, f32) {
+        (point.x, point.y)
+    }
+}
+
+impl Add for FPoint {
+    type Output = FPoint;
+
+    fn add(self, rhs: FPoint) -> FPoint {
+        self.offset(rhs.x, rhs.y)
+    }
+}
+
+impl AddAssign for FPoint {
+    fn add_assign(&mut self, rhs: FPoint) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl Neg for FPoint {
+    type Output = FPoint;
+
+    fn neg(self) -> FPoint {
+        FPoint::new(-self.x, -self.y)
+    }
+}
+
+impl Sub for FPoint {
+    type Output = FPoint;
+
+    fn sub(self, rhs: FPoint) -> FPoint {
+        self.offset(-rhs.x, -rhs.y)
+    }
+}
+
+impl SubAssign for FPoint {
+    fn sub_assign(&mut self, rhs: FPoint) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Mul<f32> for FPoint {
+    type Output = FPoint;
+
+    fn mul(self, rhs: f32) -> FPoint {
+        self.scale(rhs)
+    }
+}
+
+impl MulAssign<f32> for FPoint {
+    fn mul_assign(&mut self, rhs: f32) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl Div<f32> for FPoint {
+    type Output = FPoint;
+
+    fn div(self, rhs: f32) -> FPoint {
+        FPoint::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl DivAssign<f32> for FPoint {
+    fn div_assign(&mut self, rhs: f32) {
+        self.x /= rhs;
+        self.y /= rhs;
+    }
+}
+
+/// A rectangle with floating point coordinates.
+///
+/// Unlike `Rect`, an `FRect` can be empty: its width and height are never
+/// negative, but can be zero.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl FRect {
+    /// Creates a new rectangle from the given values.
+    ///
+    /// A negative `width` or `height` is replaced with 0.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> FRect {
+        FRect { x, y, w: width.max(0.0), h: height.max(0.0) }
+    }
+
+    /// Creates a new rectangle centered on the given position.
+    pub fn from_center<P>(center: P, width: f32, height: f32) -> FRect where P: Into<FPoint> {
+        let mut rect = FRect::new(0.0, 0.0, width, height);
+        rect.center_on(center);
+        rect
+    }
+
+    /// The horizontal position of this rectangle.
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+
+    /// The vertical position of this rectangle.
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    /// The width of this rectangle.
+    pub fn width(&self) -> f32 {
+        self.w
+    }
+
+    /// The height of this rectangle.
+    pub fn height(&self) -> f32 {
+        self.h
+    }
+
+    /// Returns the width and height of this rectangle.
+    pub fn size(&self) -> (f32, f32) {
+        (self.w, self.h)
+    }
+
+    /// Sets the horizontal position of this rectangle to the given value.
+    pub fn set_x(&mut self, x: f32) {
+        self.x = x;
+    }
+
+    /// Sets the vertical position of this rectangle to the given value.
+    pub fn set_y(&mut self, y: f32) {
+        self.y = y;
+    }
+
+    /// Sets the width of this rectangle to the given value, or 0 if it is negative.
+    pub fn set_width(&mut self, width: f32) {
+        self.w = width.max(0.0);
+    }
+
+    /// Sets the height of this rectangle to the given value, or 0 if it is negative.
+    pub fn set_height(&mut self, height: f32) {
+        self.h = height.max(0.0);
+    }
+
+    /// Returns the x-position of the left side of this rectangle.
+    pub fn left(&self) -> f32 {
+        self.x
+    }
+
+    /// Returns the x-position of the right side of this rectangle.
+    pub fn right(&self) -> f32 {
+        self.x + self.w
+    }
+
+    /// Returns the y-position of the top side of this rectangle.
+    pub fn top(&self) -> f32 {
+        self.y
+    }
+
+    /// Returns the y-position of the bottom side of this rectangle.
+    pub fn bottom(&self) -> f32 {
+        self.y + self.h
+    }
+
+    /// Returns `true` if the width or the height of this rectangle is zero.
+    pub fn is_empty(&self) -> bool {
+        // Written this way so that a NaN size is empty too
+        !(self.w > 0.0 && self.h > 0.0)
+    }
+
+    /// Returns the center position of this rectangle.
+    pub fn center(&self) -> FPoint {
+        FPoint::new(self.x + self.w / 2.0, self.y + self.h / 2.0)
+    }
+
+    /// Returns the top-left corner of this rectangle.
+    pub fn top_left(&self) -> FPoint {
+        FPoint::new(self.left(), self.top())
+    }
+
+    /// Returns the top-right corner of this rectangle.
+    pub fn top_right(&self) -> FPoint {
+        FPoint::new(self.right(), self.top())
+    }
+
+    /// Returns the bottom-left corner of this rectangle.
+    pub fn bottom_left(&self) -> FPoint {
+        FPoint::new(self.left(), self.bottom())
+    }
+
+    /// Returns the bottom-right corner of this rectangle.
+    pub fn bottom_right(&self) -> FPoint {
+        FPoint::new(self.right(), self.bottom())
+    }
+
+    /// Centers the rectangle on the given point.
+    pub fn center_on<P>(&mut self, point: P) where P: Into<FPoint> {
+        let point = point.into();
+        self.x = point.x - self.w / 2.0;
+        self.y = point.y - self.h / 2.0;
+    }
+
+    /// Moves this rect by the given values.
+    pub fn offset(&mut self, x: f32, y: f32) {
+        self.x += x;
+        self.y += y;
+    }
+
+    /// Moves this rect to the given position.
+    pub fn reposition<P>(&mut self, point: P) where P: Into<FPoint> {
+        let point = point.into();
+        self.x = point.x;
+        self.y = point.y;
+    }
+
+    /// Resizes this rect to the given size, replacing negative values with 0.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.set_width(width);
+        self.set_height(height);
+    }
+
+    /// Checks whether this rectangle contains a given point.
+    ///
+    /// Like `Rect::contains_point`, points along the right and bottom edges
+    /// are not considered to be inside the rectangle.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sdl2::rect::{FRect, FPoint};
+    /// let rect = FRect::new(1.0, 2.0, 3.0, 4.0);
+    /// assert!(rect.contains_point(FPoint::new(1.0, 2.0)));
+    /// assert!(rect.contains_point(FPoint::new(3.5, 5.5)));
+    /// assert!(!rect.contains_point(FPoint::new(4.0, 6.0)));
+    /// ```
+    pub fn contains_point<P>(&self, point: P) -> bool where P: Into<FPoint> {
+        let point = point.into();
+        point.x >= self.left() && point.x < self.right() &&
+            point.y >= self.top() && point.y < self.bottom()
+    }
+
+    /// Checks whether this rectangle completely contains another rectangle.
+    ///
+    /// An empty rectangle is contained if it lies within the bounds of `self`.
+    pub fn contains_rect(&self, other: FRect) -> bool {
+        other.left() >= self.left() && other.right() <= self.right() &&
+            other.top() >= self.top() && other.bottom() <= self.bottom()
+    }
+
+    /// Determines whether two rectangles intersect.
+    ///
+    /// Rectangles that share an edge but don't actually overlap are not
+    /// considered to intersect.
+    pub fn has_intersection(&self, other: FRect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Calculates the intersection of two rectangles.
+    ///
+    /// Returns `None` if the two rectangles don't overlap.
+    ///
+    /// The bitwise AND operator `&` can also be used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use sdl2::rect::FRect;
+    /// let rect = FRect::new(0.0, 0.0, 5.0, 5.0);
+    /// assert_eq!(rect.intersection(FRect::new(2.5, 2.0, 5.0, 5.0)),
+    ///            Some(FRect::new(2.5, 2.0, 2.5, 3.0)));
+    /// assert_eq!(rect.intersection(FRect::new(5.0, 0.0, 5.0, 5.0)), None);
+    /// ```
+    pub fn intersection(&self, other: FRect) -> Option<FRect> {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        let rect = FRect::new(left, top, right - left, bottom - top);
+
+        if rect.is_empty() {
+            None
+        } else {
+            Some(rect)
+        }
+    }
+
+    /// Calculates the union of two rectangles (i.e. the smallest rectangle
+    /// that contains both).
+    ///
+    /// Like `SDL_UnionRect`, empty rectangles are ignored.
+    ///
+    /// The bitwise OR operator `|` can also be used.
+    pub fn union(&self, other: FRect) -> FRect {
+        if other.is_empty() {
+            return *self;
+        } else if self.is_empty() {
+            return other;
+        }
+        let left = self.left().min(other.left());
+        let top = self.top().min(other.top());
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        FRect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Calculates the intersection of a rectangle and a line segment and
+    /// returns the points of their intersection, using the Liang-Barsky
+    /// algorithm.
+    ///
+    /// Unlike `Rect::intersect_line`, the edges of the rectangle are included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::rect::{FRect, FPoint};
+    /// let rect = FRect::new(0.0, 0.0, 4.0, 4.0);
+    /// assert_eq!(rect.intersect_line(FPoint::new(-2.0, 2.0), FPoint::new(6.0, 2.0)),
+    ///            Some((FPoint::new(0.0, 2.0), FPoint::new(4.0, 2.0))));
+    /// assert_eq!(rect.intersect_line(FPoint::new(-2.0, 5.0), FPoint::new(6.0, 5.0)), None);
+    /// ```
+    pub fn intersect_line(&self, start: FPoint, end: FPoint) -> Option<(FPoint, FPoint)> {
+        if self.is_empty() {
+            return None;
+        }
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+        // Each edge as (p, q): the segment enters or leaves the rectangle at t = q / p.
+        let edges = [(-dx, start.x - self.left()), (dx, self.right() - start.x),
+                     (-dy, start.y - self.top()), (dy, self.bottom() - start.y)];
+
+        for &(p, q) in &edges {
+            if p == 0.0 {
+                // Parallel to the edge, and outside of it
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+                if t0 > t1 {
+                    return None;
+                }
+            }
+        }
+
+        Some((start + FPoint::new(dx, dy) * t0, start + FPoint::new(dx, dy) * t1))
+    }
+
+    /// Converts this rectangle to integer coordinates, rounding each edge with
+    /// `mode`.
+    ///
+    /// Rounding the edges rather than the size keeps adjacent rectangles
+    /// adjacent. Returns `None` if the rounded rectangle is empty, since a
+    /// `Rect` cannot be.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sdl2::rect::{FRect, Rect, RoundingMode};
+    /// let rect = FRect::new(0.4, 0.6, 1.2, 2.0);
+    /// assert_eq!(rect.to_rect(RoundingMode::Nearest), Some(Rect::new(0, 1, 2, 2)));
+    /// assert_eq!(rect.to_rect(RoundingMode::Floor), Some(Rect::new(0, 0, 1, 2)));
+    /// assert_eq!(rect.to_rect(RoundingMode::Ceil), Some(Rect::new(1, 1, 1, 2)));
+    /// assert_eq!(FRect::new(0.2, 0.2, 0.2, 0.2).to_rect(RoundingMode::Nearest), None);
+    /// ```
+    pub fn to_rect(&self, mode: RoundingMode) -> Option<Rect> {
+        let left = mode.round(self.left());
+        let top = mode.round(self.top());
+        let right = mode.round(self.right());
+        let bottom = mode.round(self.bottom());
+
+        if right > left && bottom > top {
+            Some(Rect::new(left, top, (right - left) as u32, (bottom - top) as u32))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Rect> for FRect {
+    /// Converts the coordinates exactly, for coordinates smaller than 2^24 in absolute value.
+    fn from(rect: Rect) -> FRect {
+        FRect::new(rect.x() as f32, rect.y() as f32, rect.width() as f32, rect.height() as f32)
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for FRect {
+    fn from((x, y, width, height): (f32, f32, f32, f32)) -> FRect {
+        FRect::new(x, y, width, height)
+    }
+}
+
+impl From<FRect> for (f32, f32, f32, f32) {
+    fn from(rect: FRect) -> (f32, f32, f32, f32) {
+        (rect.x, rect.y, rect.w, rect.h)
+    }
+}
+
+impl BitAnd<FRect> for FRect {
+    type Output = Option<FRect>;
+    fn bitand(self, rhs: FRect) -> Option<FRect> { self.intersection(rhs) }
+}
+
+impl BitOr<FRect> for FRect {
+    type Output = FRect;
+    fn bitor(self, rhs: FRect) -> FRect { self.union(rhs) }
+}
+
 #[cfg(test)]
 mod test {
-    use super::{Rect, Point, max_int_value, min_int_value};
+    use super::{Rect, Point, FRect, FPoint, RoundingMode, max_int_value, min_int_value};
 
     /// Used to compare "literal" (unclamped) rect values.
     fn tuple(x: i32, y: i32, w: u32, h: u32) -> (i32, i32, u32, u32) {
@@ -1140,4 +1644,60 @@ mod test {
         );
     }
 
+    #[test]
+    fn frect_intersection_and_union() {
+        let rect = FRect::new(0.0, 0.0, 2.0, 2.0);
+        let other = FRect::new(1.5, -1.0, 2.0, 2.0);
+        assert_eq!(rect & other, Some(FRect::new(1.5, 0.0, 0.5, 1.0)));
+        assert_eq!(rect | other, FRect::new(0.0, -1.0, 3.5, 3.0));
+        assert!(!rect.has_intersection(FRect::new(2.0, 0.0, 1.0, 1.0)));
+        assert_eq!(rect | FRect::new(9.0, 9.0, 0.0, 0.0), rect);
+        assert!(rect.contains_rect(FRect::new(0.5, 0.5, 1.5, 1.5)));
+        assert!(!rect.contains_point((2.0, 1.0)));
+        assert_eq!(FRect::new(1.0, 1.0, -3.0, 2.0).size(), (0.0, 2.0));
+        assert_eq!(FRect::from_center((1.0, 1.0), 1.0, 3.0), FRect::new(0.5, -0.5, 1.0, 3.0));
+    }
+
+    #[test]
+    fn frect_intersect_line() {
+        let rect = FRect::new(0.0, 0.0, 4.0, 2.0);
+        assert_eq!(
+            rect.intersect_line(FPoint::new(-1.0, -1.0), FPoint::new(5.0, 5.0)),
+            Some((FPoint::new(0.0, 0.0), FPoint::new(2.0, 2.0)))
+        );
+        // Inside
+        assert_eq!(
+            rect.intersect_line(FPoint::new(1.0, 1.0), FPoint::new(3.0, 1.5)),
+            Some((FPoint::new(1.0, 1.0), FPoint::new(3.0, 1.5)))
+        );
+        // Vertical, clipped at both ends
+        assert_eq!(
+            rect.intersect_line(FPoint::new(1.0, 5.0), FPoint::new(1.0, -5.0)),
+            Some((FPoint::new(1.0, 2.0), FPoint::new(1.0, 0.0)))
+        );
+        assert_eq!(rect.intersect_line(FPoint::new(5.0, 0.0), FPoint::new(5.0, 2.0)), None);
+        assert_eq!(rect.intersect_line(FPoint::new(3.0, -2.0), FPoint::new(6.0, 1.0)), None);
+        assert_eq!(FRect::new(0.0, 0.0, 0.0, 1.0)
+                   .intersect_line(FPoint::new(0.0, 0.0), FPoint::new(0.0, 1.0)), None);
+    }
+
+    #[test]
+    fn float_to_integer_rounding() {
+        let point = FPoint::new(-2.5, 2.5);
+        assert_eq!(point.to_point(RoundingMode::Nearest), Point::new(-3, 3));
+        assert_eq!(point.to_point(RoundingMode::Floor), Point::new(-3, 2));
+        assert_eq!(point.to_point(RoundingMode::Ceil), Point::new(-2, 3));
+        assert_eq!(point.to_point(RoundingMode::Truncate), Point::new(-2, 2));
+        assert_eq!(FPoint::new(1e20, f32::NAN).to_point(RoundingMode::Nearest),
+                   Point::new(max_int_value() as i32, 0));
+
+        // Adjacent rectangles stay adjacent
+        let left = FRect::new(0.0, 0.0, 1.4, 1.0).to_rect(RoundingMode::Nearest).unwrap();
+        let right = FRect::new(1.4, 0.0, 1.4, 1.0).to_rect(RoundingMode::Nearest).unwrap();
+        assert_eq!(left.right(), right.left());
+        assert_eq!(right, Rect::new(1, 0, 2, 1));
+        assert_eq!(FRect::from(Rect::new(-3, 4, 5, 6)), FRect::new(-3.0, 4.0, 5.0, 6.0));
+        assert_eq!(FPoint::from(Point::new(-3, 4)) * 2.0 - FPoint::new(1.0, 1.0),
+                   FPoint::new(-7.0, 7.0));
+    }
 }
@@ -0,0 +1,786 @@
+//! Packing of many small images into a few textures.
+//!
+//! Drawing many sprites from a single texture is much cheaper than switching between many
+//! textures. An `AtlasBuilder` takes named `Surface`s, packs them into one or more pages with a
+//! `PackingAlgorithm`, and uploads each page to a static texture. The resulting `Atlas` maps
+//! each name to its texture and `Rect`.
+//!
+//! Sprites are separated by `padding` transparent pixels, and their border pixels can be
+//! repeated `extrusion` times around them, to avoid bleeding of neighbouring sprites when
+//! textures are scaled with linear filtering.
+//!
+//! The layout can be computed offline with `AtlasBuilder::pack`, saved in a simple text format
+//! (see `AtlasLayout`), and used later with `AtlasBuilder::build_with_layout`.
+//!
+//! # Example
+//! ```no_run
+//! use sdl2::render::atlas::AtlasBuilder;
+//! use sdl2::surface::Surface;
+//!
+//! let sdl_context = sdl2::init().unwrap();
+//! let video_subsystem = sdl_context.video().unwrap();
+//! let window = video_subsystem.window("atlas", 800, 600).build().unwrap();
+//! let canvas = window.into_canvas().build().unwrap();
+//! let texture_creator = canvas.texture_creator();
+//!
+//! let player = Surface::load_bmp("player.bmp").unwrap();
+//! let enemy = Surface::load_bmp("enemy.bmp").unwrap();
+//!
+//! let atlas = AtlasBuilder::new(1024, 1024)
+//!     .padding(1)
+//!     .extrusion(1)
+//!     .add("player", &player)
+//!     .add("enemy", &enemy)
+//!     .build(&texture_creator)
+//!     .unwrap();
+//!
+//! let (texture, rect) = atlas.region("player").unwrap();
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::pixels::PixelFormatEnum;
+use crate::rect::Rect;
+use crate::surface::{Surface, SurfaceRef};
+use super::{BlendMode, Texture, TextureCreator};
+
+/// The format of the pages of an atlas.
+const PAGE_FORMAT: PixelFormatEnum = PixelFormatEnum::ARGB8888;
+
+/// How sprites are placed in the pages of an atlas.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PackingAlgorithm {
+    /// Bottom-left skyline packing: fast, and efficient for sprites of similar heights.
+    Skyline,
+    /// Maximal rectangles with best short side fit: slower, but usually denser.
+    MaxRects,
+}
+
+#[derive(Debug)]
+pub enum AtlasError {
+    /// The sprite does not fit in an empty page, with its padding and extrusion.
+    SpriteTooLarge { name: String, width: u32, height: u32 },
+    /// Two sprites have the same name.
+    DuplicateName(String),
+    /// The layout has no region for this sprite, or its size differs from the sprite's.
+    LayoutMismatch(String),
+    /// A serialized layout could not be parsed.
+    InvalidLayout { line: usize, message: String },
+    SdlError(String),
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::AtlasError::*;
+
+        match *self {
+            SpriteTooLarge { ref name, width, height } =>
+                write!(f, "Sprite {} ({}x{}) does not fit in an atlas page", name, width, height),
+            DuplicateName(ref name) => write!(f, "Duplicate sprite name: {}", name),
+            LayoutMismatch(ref name) => write!(f, "Sprite {} does not match the atlas layout", name),
+            InvalidLayout { line, ref message } => write!(f, "Invalid atlas layout at line {}: {}", line, message),
+            SdlError(ref e) => write!(f, "SDL error: {}", e),
+        }
+    }
+}
+
+impl StdError for AtlasError {
+    fn description(&self) -> &str {
+        use self::AtlasError::*;
+
+        match *self {
+            SpriteTooLarge { .. } => "sprite too large for the atlas",
+            DuplicateName(_) => "duplicate sprite name",
+            LayoutMismatch(_) => "sprite does not match the atlas layout",
+            InvalidLayout { .. } => "invalid atlas layout",
+            SdlError(ref e) => e,
+        }
+    }
+}
+
+/// The place of a sprite in an atlas.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct AtlasRegion {
+    pub name: String,
+    /// The index of the page (texture) the sprite is in.
+    pub page: usize,
+    /// The area of the sprite in its page, without padding nor extrusion.
+    pub rect: Rect,
+}
+
+/// The sizes of the pages of an atlas, and the regions of its sprites.
+///
+/// It can be saved and loaded as text, with one page or region per line:
+///
+/// ```text
+/// page <width> <height>
+/// region <page> <x> <y> <width> <height> <name>
+/// ```
+///
+/// Names extend to the end of their line, and may contain spaces.
+///
+/// ```
+/// use sdl2::render::atlas::AtlasLayout;
+///
+/// let text = "page 64 32\nregion 0 1 1 16 16 player idle\n";
+/// let layout: AtlasLayout = text.parse().unwrap();
+/// assert_eq!(layout.region("player idle").unwrap().rect.width(), 16);
+/// assert_eq!(layout.to_string(), text);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct AtlasLayout {
+    pub pages: Vec<(u32, u32)>,
+    pub regions: Vec<AtlasRegion>,
+}
+
+impl AtlasLayout {
+    /// Finds the region of a sprite.
+    pub fn region(&self, name: &str) -> Option<&AtlasRegion> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+}
+
+impl fmt::Display for AtlasLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(width, height) in &self.pages {
+            writeln!(f, "page {} {}", width, height)?;
+        }
+        for region in &self.regions {
+            writeln!(f, "region {} {} {} {} {} {}", region.page, region.rect.x(), region.rect.y(),
+                     region.rect.width(), region.rect.height(), region.name)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AtlasLayout {
+    type Err = AtlasError;
+
+    fn from_str(s: &str) -> Result<AtlasLayout, AtlasError> {
+        let mut layout = AtlasLayout::default();
+        for (index, line) in s.lines().enumerate() {
+            let invalid = |message: &str| AtlasError::InvalidLayout {
+                line: index + 1,
+                message: message.to_owned(),
+            };
+            let line = line.trim_start();
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.splitn(7, ' ');
+            match fields.next() {
+                Some("page") => {
+                    let mut size = [0u32; 2];
+                    for value in &mut size {
+                        *value = fields.next().and_then(|v| v.parse().ok())
+                            .ok_or_else(|| invalid("expected a page width and height"))?;
+                    }
+                    layout.pages.push((size[0], size[1]));
+                },
+                Some("region") => {
+                    let page: usize = fields.next().and_then(|v| v.parse().ok())
+                        .ok_or_else(|| invalid("expected a page index"))?;
+                    let x: i32 = fields.next().and_then(|v| v.parse().ok())
+                        .ok_or_else(|| invalid("expected an x coordinate"))?;
+                    let y: i32 = fields.next().and_then(|v| v.parse().ok())
+                        .ok_or_else(|| invalid("expected a y coordinate"))?;
+                    let width: u32 = fields.next().and_then(|v| v.parse().ok())
+                        .ok_or_else(|| invalid("expected a width"))?;
+                    let height: u32 = fields.next().and_then(|v| v.parse().ok())
+                        .ok_or_else(|| invalid("expected a height"))?;
+                    let name = fields.next().filter(|name| !name.is_empty())
+                        .ok_or_else(|| invalid("expected a name"))?;
+                    if page >= layout.pages.len() {
+                        return Err(invalid("region on an undeclared page"));
+                    }
+                    layout.regions.push(AtlasRegion {
+                        name: name.to_owned(),
+                        page,
+                        rect: Rect::new(x, y, width, height),
+                    });
+                },
+                _ => return Err(invalid("expected `page` or `region`")),
+            }
+        }
+        Ok(layout)
+    }
+}
+
+/// A bin in which rectangles are placed, for one page.
+trait Packer {
+    /// Finds a place for a `width`x`height` rectangle and marks it as used.
+    fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)>;
+}
+
+/// Skyline bottom-left packer: the top of the used area is kept as a list of horizontal
+/// segments, and each rectangle is put where its top would be the lowest.
+struct SkylinePacker {
+    width: u32,
+    height: u32,
+    // (x, y, width) of each segment, from left to right
+    skyline: Vec<(u32, u32, u32)>,
+}
+
+impl SkylinePacker {
+    fn new(width: u32, height: u32) -> SkylinePacker {
+        SkylinePacker { width, height, skyline: vec![(0, 0, width)] }
+    }
+
+    /// The y coordinate of a `width` wide rectangle whose left side is on segment `index`.
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.skyline[index].0;
+        if x + width > self.width {
+            return None;
+        }
+        let mut y = 0;
+        let mut remaining = width;
+        for &(_, segment_y, segment_width) in &self.skyline[index..] {
+            y = y.max(segment_y);
+            if y + height > self.height {
+                return None;
+            }
+            if segment_width >= remaining {
+                return Some(y);
+            }
+            remaining -= segment_width;
+        }
+        None
+    }
+}
+
+impl Packer for SkylinePacker {
+    fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let mut best: Option<(usize, u32, u32)> = None;
+        for index in 0..self.skyline.len() {
+            if let Some(y) = self.fit(index, width, height) {
+                let x = self.skyline[index].0;
+                let better = match best {
+                    Some((_, best_x, best_y)) => (y + height, x) < (best_y + height, best_x),
+                    None => true,
+                };
+                if better {
+                    best = Some((index, x, y));
+                }
+            }
+        }
+        let (index, x, y) = best?;
+
+        // Raise the skyline over the new rectangle
+        self.skyline.insert(index, (x, y + height, width));
+        let right = x + width;
+        let i = index + 1;
+        while i < self.skyline.len() {
+            let (segment_x, segment_y, segment_width) = self.skyline[i];
+            if segment_x >= right {
+                break;
+            }
+            let segment_right = segment_x + segment_width;
+            if segment_right <= right {
+                self.skyline.remove(i);
+            } else {
+                self.skyline[i] = (right, segment_y, segment_right - right);
+                break;
+            }
+        }
+        // Merge neighbouring segments of the same height
+        let mut i = 1;
+        while i < self.skyline.len() {
+            if self.skyline[i - 1].1 == self.skyline[i].1 {
+                self.skyline[i - 1].2 += self.skyline[i].2;
+                self.skyline.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        Some((x, y))
+    }
+}
+
+/// Maximal rectangles packer: the free area is kept as a list of possibly overlapping maximal
+/// rectangles, and each rectangle is put in the free one leaving the shortest side.
+struct MaxRectsPacker {
+    // (x, y, width, height)
+    free: Vec<(u32, u32, u32, u32)>,
+}
+
+impl MaxRectsPacker {
+    fn new(width: u32, height: u32) -> MaxRectsPacker {
+        MaxRectsPacker { free: vec![(0, 0, width, height)] }
+    }
+}
+
+impl Packer for MaxRectsPacker {
+    fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let mut best: Option<(u32, u32, u32, u32)> = None;
+        for &(x, y, free_width, free_height) in &self.free {
+            if width <= free_width && height <= free_height {
+                let short_side = (free_width - width).min(free_height - height);
+                let long_side = (free_width - width).max(free_height - height);
+                let better = match best {
+                    Some((_, _, best_short, best_long)) => (short_side, long_side) < (best_short, best_long),
+                    None => true,
+                };
+                if better {
+                    best = Some((x, y, short_side, long_side));
+                }
+            }
+        }
+        let (x, y, _, _) = best?;
+
+        // Split every free rectangle intersecting the used one
+        let used = (x, y, x + width, y + height);
+        let mut free = Vec::with_capacity(self.free.len() + 4);
+        for &(fx, fy, fw, fh) in &self.free {
+            let (fr, fb) = (fx + fw, fy + fh);
+            if used.0 >= fr || used.2 <= fx || used.1 >= fb || used.3 <= fy {
+                free.push((fx, fy, fw, fh));
+                continue;
+            }
+            if used.0 > fx {
+                free.push((fx, fy, used.0 - fx, fh));
+            }
+            if used.2 < fr {
+                free.push((used.2, fy, fr - used.2, fh));
+            }
+            if used.1 > fy {
+                free.push((fx, fy, fw, used.1 - fy));
+            }
+            if used.3 < fb {
+                free.push((fx, used.3, fw, fb - used.3));
+            }
+        }
+        // Remove the free rectangles contained in another one
+        let contains = |a: &(u32, u32, u32, u32), b: &(u32, u32, u32, u32)| {
+            b.0 >= a.0 && b.1 >= a.1 && b.0 + b.2 <= a.0 + a.2 && b.1 + b.3 <= a.1 + a.3
+        };
+        let mut pruned: Vec<(u32, u32, u32, u32)> = Vec::with_capacity(free.len());
+        for (i, rect) in free.iter().enumerate() {
+            let redundant = free.iter().enumerate().any(|(j, other)| {
+                i != j && contains(other, rect) && (rect != other || j < i)
+            });
+            if !redundant {
+                pruned.push(*rect);
+            }
+        }
+        self.free = pruned;
+        Some((x, y))
+    }
+}
+
+/// Places rectangles of the given sizes in as many `page_width`x`page_height` pages as needed.
+///
+/// Returns the page and position of each rectangle, in the order they were given, or the index
+/// of the first one which does not fit in an empty page.
+fn pack(algorithm: PackingAlgorithm, page_width: u32, page_height: u32, sizes: &[(u32, u32)])
+        -> Result<Vec<(usize, u32, u32)>, usize> {
+    // Placing big rectangles first gives denser pages
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (aw, ah) = sizes[a];
+        let (bw, bh) = sizes[b];
+        (bh, bw).cmp(&(ah, aw))
+    });
+
+    let new_packer = || -> Box<dyn Packer> {
+        match algorithm {
+            PackingAlgorithm::Skyline => Box::new(SkylinePacker::new(page_width, page_height)),
+            PackingAlgorithm::MaxRects => Box::new(MaxRectsPacker::new(page_width, page_height)),
+        }
+    };
+    let mut pages: Vec<Box<dyn Packer>> = Vec::new();
+    let mut placements = vec![(0, 0, 0); sizes.len()];
+    for index in order {
+        let (width, height) = sizes[index];
+        if width > page_width || height > page_height {
+            return Err(index);
+        }
+        let mut placed = None;
+        for (page, packer) in pages.iter_mut().enumerate() {
+            if let Some((x, y)) = packer.insert(width, height) {
+                placed = Some((page, x, y));
+                break;
+            }
+        }
+        let placement = match placed {
+            Some(placement) => placement,
+            None => {
+                let mut packer = new_packer();
+                let (x, y) = packer.insert(width, height).ok_or(index)?;
+                pages.push(packer);
+                (pages.len() - 1, x, y)
+            },
+        };
+        placements[index] = placement;
+    }
+    Ok(placements)
+}
+
+/// Repeats the border pixels of `rect` `extrusion` times around it, in a 32 bits per pixel
+/// buffer.
+fn extrude(pixels: &mut [u8], pitch: usize, rect: Rect, extrusion: u32) {
+    if extrusion == 0 || rect.width() == 0 || rect.height() == 0 {
+        return;
+    }
+    let e = extrusion as usize;
+    let (x, y) = (rect.x() as usize, rect.y() as usize);
+    let (w, h) = (rect.width() as usize, rect.height() as usize);
+
+    // Left and right sides, on the rows of the sprite
+    for row in y..y + h {
+        let line = &mut pixels[row * pitch..(row + 1) * pitch];
+        let mut left = [0u8; 4];
+        left.copy_from_slice(&line[x * 4..x * 4 + 4]);
+        let mut right = [0u8; 4];
+        right.copy_from_slice(&line[(x + w - 1) * 4..(x + w) * 4]);
+        for i in 1..=e {
+            line[(x - i) * 4..(x - i + 1) * 4].copy_from_slice(&left);
+            line[(x + w - 1 + i) * 4..(x + w + i) * 4].copy_from_slice(&right);
+        }
+    }
+    // Top and bottom sides, including the corners
+    let (start, end) = ((x - e) * 4, (x + w + e) * 4);
+    for i in 1..=e {
+        let top = y * pitch;
+        let (before, after) = pixels.split_at_mut(top);
+        before[(y - i) * pitch + start..(y - i) * pitch + end].copy_from_slice(&after[start..end]);
+
+        let bottom = (y + h - 1) * pitch;
+        let (before, after) = pixels.split_at_mut(bottom + pitch);
+        after[(i - 1) * pitch + start..(i - 1) * pitch + end].copy_from_slice(&before[bottom + start..bottom + end]);
+    }
+}
+
+/// Textures holding many sprites, with the region of each sprite.
+///
+/// Built with `AtlasBuilder`. `T` is the texture type, `Texture` unless the atlas has been
+/// converted with `map_textures`.
+pub struct Atlas<T> {
+    textures: Vec<T>,
+    layout: AtlasLayout,
+    indices: HashMap<String, usize>,
+}
+
+impl<T> Atlas<T> {
+    fn new(textures: Vec<T>, layout: AtlasLayout) -> Atlas<T> {
+        let indices = layout.regions.iter().enumerate()
+            .map(|(index, region)| (region.name.clone(), index))
+            .collect();
+        Atlas { textures, layout, indices }
+    }
+
+    /// The texture and area of a sprite.
+    pub fn region(&self, name: &str) -> Option<(&T, Rect)> {
+        let region = &self.layout.regions[*self.indices.get(name)?];
+        Some((&self.textures[region.page], region.rect))
+    }
+
+    /// The textures of the pages.
+    pub fn textures(&self) -> &[T] {
+        &self.textures
+    }
+
+    pub fn layout(&self) -> &AtlasLayout {
+        &self.layout
+    }
+
+    /// Converts the textures of the atlas, e.g. to `SharedTexture`s.
+    pub fn map_textures<U, F: FnMut(T) -> U>(self, f: F) -> Atlas<U> {
+        Atlas {
+            textures: self.textures.into_iter().map(f).collect(),
+            layout: self.layout,
+            indices: self.indices,
+        }
+    }
+}
+
+/// The type that allows you to build texture atlases; see the module documentation.
+pub struct AtlasBuilder<'a> {
+    max_width: u32,
+    max_height: u32,
+    padding: u32,
+    extrusion: u32,
+    algorithm: PackingAlgorithm,
+    sprites: Vec<(String, &'a SurfaceRef)>,
+}
+
+impl<'a> AtlasBuilder<'a> {
+    /// Initializes a new `AtlasBuilder` whose pages are at most `max_width`x`max_height`.
+    ///
+    /// Pages are shrunk to the area they use.
+    pub fn new(max_width: u32, max_height: u32) -> AtlasBuilder<'a> {
+        AtlasBuilder {
+            max_width,
+            max_height,
+            padding: 0,
+            extrusion: 0,
+            algorithm: PackingAlgorithm::MaxRects,
+            sprites: Vec::new(),
+        }
+    }
+
+    /// Sets the number of transparent pixels between sprites; defaults to 0.
+    pub fn padding(&mut self, padding: u32) -> &mut AtlasBuilder<'a> {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets how many times the border pixels of each sprite are repeated around it; defaults to 0.
+    pub fn extrusion(&mut self, extrusion: u32) -> &mut AtlasBuilder<'a> {
+        self.extrusion = extrusion;
+        self
+    }
+
+    /// Sets the packing algorithm; defaults to `PackingAlgorithm::MaxRects`.
+    pub fn algorithm(&mut self, algorithm: PackingAlgorithm) -> &mut AtlasBuilder<'a> {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Adds a sprite.
+    pub fn add(&mut self, name: &str, surface: &'a SurfaceRef) -> &mut AtlasBuilder<'a> {
+        self.sprites.push((name.to_owned(), surface));
+        self
+    }
+
+    /// Computes the layout of the atlas, without creating any texture.
+    pub fn pack(&self) -> Result<AtlasLayout, AtlasError> {
+        self.pack_sizes(&self.sprites.iter()
+            .map(|&(ref name, surface)| (name.as_str(), surface.size()))
+            .collect::<Vec<_>>())
+    }
+
+    fn pack_sizes(&self, sprites: &[(&str, (u32, u32))]) -> Result<AtlasLayout, AtlasError> {
+        let mut names = HashSet::new();
+        for &(name, _) in sprites {
+            if !names.insert(name) {
+                return Err(AtlasError::DuplicateName(name.to_owned()));
+            }
+        }
+
+        let margin = 2 * self.extrusion + self.padding;
+        let sizes: Vec<(u32, u32)> = sprites.iter()
+            .map(|&(_, (width, height))| (width + margin, height + margin))
+            .collect();
+        // The padding after the last sprites of a row or column may exceed the page
+        let placements = pack(self.algorithm, self.max_width + self.padding, self.max_height + self.padding, &sizes)
+            .map_err(|index| {
+                let (name, (width, height)) = sprites[index];
+                AtlasError::SpriteTooLarge { name: name.to_owned(), width, height }
+            })?;
+
+        let mut layout = AtlasLayout::default();
+        for (&(name, (width, height)), &(page, x, y)) in sprites.iter().zip(&placements) {
+            if page >= layout.pages.len() {
+                layout.pages.resize(page + 1, (0, 0));
+            }
+            let page_size = &mut layout.pages[page];
+            page_size.0 = page_size.0.max(x + width + 2 * self.extrusion);
+            page_size.1 = page_size.1.max(y + height + 2 * self.extrusion);
+            layout.regions.push(AtlasRegion {
+                name: name.to_owned(),
+                page,
+                rect: Rect::new((x + self.extrusion) as i32, (y + self.extrusion) as i32, width, height),
+            });
+        }
+        Ok(layout)
+    }
+
+    /// Draws the sprites in surfaces, one per page of `layout`.
+    ///
+    /// Every sprite must have a region of its size in `layout`.
+    pub fn render_pages(&self, layout: &AtlasLayout) -> Result<Vec<Surface<'static>>, AtlasError> {
+        let sdl_error = |e: crate::Error| AtlasError::SdlError(e.to_string());
+
+        let mut pages = Vec::with_capacity(layout.pages.len());
+        for &(width, height) in &layout.pages {
+            let mut page = Surface::new(width.max(1), height.max(1), PAGE_FORMAT).map_err(sdl_error)?;
+            page.set_blend_mode(BlendMode::None).map_err(sdl_error)?;
+            pages.push(page);
+        }
+
+        for &(ref name, surface) in &self.sprites {
+            let region = match layout.region(name) {
+                Some(region) if region.rect.size() == surface.size() && region.page < pages.len() => region,
+                _ => return Err(AtlasError::LayoutMismatch(name.clone())),
+            };
+            let page = &mut pages[region.page];
+            let (page_width, page_height) = page.size();
+            let rect = region.rect;
+            let e = self.extrusion as i32;
+            if rect.x() < e || rect.y() < e
+                || rect.right() + e > page_width as i32 || rect.bottom() + e > page_height as i32 {
+                return Err(AtlasError::LayoutMismatch(name.clone()));
+            }
+
+            // Copy the pixels as they are, alpha included
+            let mut sprite = surface.convert_format(PAGE_FORMAT).map_err(sdl_error)?;
+            sprite.set_blend_mode(BlendMode::None).map_err(sdl_error)?;
+            sprite.blit(None, page, rect).map_err(sdl_error)?;
+
+            let pitch = page.pitch() as usize;
+            let extrusion = self.extrusion;
+            page.with_lock_mut(|pixels| extrude(pixels, pitch, rect, extrusion));
+        }
+        Ok(pages)
+    }
+
+    fn build_textures<'r, T, X, F>(&self, layout: AtlasLayout, creator: &'r TextureCreator<T>, mut update: F)
+            -> Result<Atlas<X>, AtlasError>
+        where F: FnMut(&'r TextureCreator<T>, &SurfaceRef) -> Result<X, AtlasError>
+    {
+        let pages = self.render_pages(&layout)?;
+        let textures = pages.iter()
+            .map(|page| update(creator, page))
+            .collect::<Result<Vec<X>, AtlasError>>()?;
+        Ok(Atlas::new(textures, layout))
+    }
+
+    /// Packs the sprites, and creates one static texture per page.
+    #[cfg(not(feature = "unsafe_textures"))]
+    pub fn build<'r, T>(&self, creator: &'r TextureCreator<T>) -> Result<Atlas<Texture<'r>>, AtlasError> {
+        let layout = self.pack()?;
+        self.build_with_layout(creator, layout)
+    }
+
+    /// Packs the sprites, and creates one static texture per page.
+    #[cfg(feature = "unsafe_textures")]
+    pub fn build<T>(&self, creator: &TextureCreator<T>) -> Result<Atlas<Texture>, AtlasError> {
+        let layout = self.pack()?;
+        self.build_with_layout(creator, layout)
+    }
+
+    /// Creates one static texture per page of a layout computed beforehand, e.g. parsed from a
+    /// file written by `AtlasLayout::to_string`.
+    #[cfg(not(feature = "unsafe_textures"))]
+    pub fn build_with_layout<'r, T>(&self, creator: &'r TextureCreator<T>, layout: AtlasLayout)
+            -> Result<Atlas<Texture<'r>>, AtlasError> {
+        self.build_textures(layout, creator, |creator, page| {
+            let mut texture = creator.create_texture_static(PAGE_FORMAT, page.width(), page.height())
+                .map_err(|e| AtlasError::SdlError(e.to_string()))?;
+            update_page(&mut texture, page)?;
+            Ok(texture)
+        })
+    }
+
+    /// Creates one static texture per page of a layout computed beforehand, e.g. parsed from a
+    /// file written by `AtlasLayout::to_string`.
+    #[cfg(feature = "unsafe_textures")]
+    pub fn build_with_layout<T>(&self, creator: &TextureCreator<T>, layout: AtlasLayout)
+            -> Result<Atlas<Texture>, AtlasError> {
+        self.build_textures(layout, creator, |creator, page| {
+            let mut texture = creator.create_texture_static(PAGE_FORMAT, page.width(), page.height())
+                .map_err(|e| AtlasError::SdlError(e.to_string()))?;
+            update_page(&mut texture, page)?;
+            Ok(texture)
+        })
+    }
+}
+
+fn update_page(texture: &mut Texture, page: &SurfaceRef) -> Result<(), AtlasError> {
+    let pitch = page.pitch() as usize;
+    page.with_lock(|pixels| texture.update(None, pixels, pitch))
+        .map_err(|e| AtlasError::SdlError(e.to_string()))?;
+    texture.try_set_blend_mode(BlendMode::Blend)
+        .map_err(|e| AtlasError::SdlError(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{extrude, pack, AtlasBuilder, AtlasError, AtlasLayout, PackingAlgorithm};
+    use crate::rect::Rect;
+
+    fn assert_disjoint(placements: &[(usize, u32, u32)], sizes: &[(u32, u32)], page: (u32, u32)) {
+        for (i, &(page_a, xa, ya)) in placements.iter().enumerate() {
+            let (wa, ha) = sizes[i];
+            assert!(xa + wa <= page.0 && ya + ha <= page.1);
+            for (j, &(page_b, xb, yb)) in placements.iter().enumerate().skip(i + 1) {
+                let (wb, hb) = sizes[j];
+                let overlap = page_a == page_b
+                    && xa < xb + wb && xb < xa + wa && ya < yb + hb && yb < ya + ha;
+                assert!(!overlap, "{:?} and {:?} overlap", placements[i], placements[j]);
+            }
+        }
+    }
+
+    #[test]
+    fn packing_is_disjoint() {
+        let sizes: Vec<(u32, u32)> = (0..60u32)
+            .map(|i| (4 + (i * 7) % 23, 3 + (i * 11) % 19))
+            .collect();
+        for &algorithm in &[PackingAlgorithm::Skyline, PackingAlgorithm::MaxRects] {
+            let placements = pack(algorithm, 64, 64, &sizes).unwrap();
+            assert_disjoint(&placements, &sizes, (64, 64));
+            assert!(placements.iter().any(|p| p.0 > 0), "expected several pages");
+        }
+    }
+
+    #[test]
+    fn packing_fills_exactly() {
+        let sizes = vec![(8, 8); 16];
+        for &algorithm in &[PackingAlgorithm::Skyline, PackingAlgorithm::MaxRects] {
+            let placements = pack(algorithm, 32, 32, &sizes).unwrap();
+            assert!(placements.iter().all(|p| p.0 == 0));
+            assert_disjoint(&placements, &sizes, (32, 32));
+        }
+        assert_eq!(pack(PackingAlgorithm::Skyline, 32, 32, &[(8, 8), (33, 1)]), Err(1));
+    }
+
+    #[test]
+    fn layout_with_padding_and_extrusion() {
+        let mut builder = AtlasBuilder::new(64, 64);
+        builder.padding(2).extrusion(1);
+        let layout = builder.pack_sizes(&[("a", (10, 10)), ("b", (20, 5))]).unwrap();
+        assert_eq!(layout.pages.len(), 1);
+        let (a, b) = (layout.region("a").unwrap().rect, layout.region("b").unwrap().rect);
+        assert_eq!(a.size(), (10, 10));
+        assert!(a.x() >= 1 && a.y() >= 1);
+        // the extruded borders and the padding do not overlap
+        let grow = |r: Rect| (r.x() - 1, r.y() - 1, r.right() + 3, r.bottom() + 3);
+        let (ga, gb) = (grow(a), grow(b));
+        assert!(ga.2 <= gb.0 || gb.2 <= ga.0 || ga.3 <= gb.1 || gb.3 <= ga.1);
+
+        match builder.pack_sizes(&[("a", (10, 10)), ("a", (1, 1))]) {
+            Err(AtlasError::DuplicateName(ref name)) if name == "a" => (),
+            _ => panic!("expected a duplicate name error"),
+        }
+        match builder.pack_sizes(&[("big", (63, 10))]) {
+            Err(AtlasError::SpriteTooLarge { ref name, .. }) if name == "big" => (),
+            _ => panic!("expected a sprite too large error"),
+        }
+    }
+
+    #[test]
+    fn layout_round_trip() {
+        let mut builder = AtlasBuilder::new(32, 32);
+        builder.padding(1);
+        let layout = builder.pack_sizes(&[("one", (30, 30)), ("two words", (16, 8))]).unwrap();
+        assert_eq!(layout.pages.len(), 2);
+        let parsed: AtlasLayout = layout.to_string().parse().unwrap();
+        assert_eq!(parsed, layout);
+
+        assert!("page 1".parse::<AtlasLayout>().is_err());
+        assert!("region 0 0 0 1 1 a".parse::<AtlasLayout>().is_err());
+        assert!("page 4 4\nregion 0 0 0 1 1".parse::<AtlasLayout>().is_err());
+    }
+
+    #[test]
+    fn extrusion_repeats_borders() {
+        // 4x4 pixels, with a 2x2 sprite in the middle
+        let pitch = 16;
+        let mut pixels = vec![0u8; pitch * 4];
+        for (i, &(x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].iter().enumerate() {
+            pixels[y * pitch + x * 4] = i as u8 + 1;
+        }
+        extrude(&mut pixels, pitch, Rect::new(1, 1, 2, 2), 1);
+        let values: Vec<u8> = pixels.chunks(4).map(|p| p[0]).collect();
+        assert_eq!(values, vec![
+            1, 1, 2, 2,
+            1, 1, 2, 2,
+            3, 3, 4, 4,
+            3, 3, 4, 4,
+        ]);
+    }
+}
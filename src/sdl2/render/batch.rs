@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem::transmute;
+use std::ptr;
+
+use libc::c_double;
+
+use crate::Error;
+use crate::get_error_as_error;
+use crate::rect::{Point, Rect};
+use crate::sys;
+use super::{Canvas, CopySource, CustomBlendMode, InternalTexture, RenderTarget};
+
+/// A sprite of a `SpriteBatch`, customized with its chained setters.
+#[derive(Clone, Debug)]
+pub struct Sprite {
+    src: Option<Rect>,
+    dst: Option<Rect>,
+    angle: f64,
+    center: Option<Point>,
+    flip_horizontal: bool,
+    flip_vertical: bool,
+    color_mod: Option<(u8, u8, u8)>,
+    alpha_mod: Option<u8>,
+    blend_mode: Option<CustomBlendMode>,
+    layer: i32,
+}
+
+impl Sprite {
+    /// Rotates the sprite by `angle` degrees, clockwise.
+    pub fn angle(&mut self, angle: f64) -> &mut Sprite {
+        self.angle = angle;
+        self
+    }
+
+    /// Sets the center of the rotation, relative to `dst`; defaults to the center of `dst`.
+    pub fn center<P: Into<Point>>(&mut self, center: P) -> &mut Sprite {
+        self.center = Some(center.into());
+        self
+    }
+
+    pub fn flip(&mut self, horizontal: bool, vertical: bool) -> &mut Sprite {
+        self.flip_horizontal = horizontal;
+        self.flip_vertical = vertical;
+        self
+    }
+
+    /// Multiplies the colors of the sprite; defaults to the color mod of the texture.
+    pub fn color_mod(&mut self, red: u8, green: u8, blue: u8) -> &mut Sprite {
+        self.color_mod = Some((red, green, blue));
+        self
+    }
+
+    /// Multiplies the alpha of the sprite; defaults to the alpha mod of the texture.
+    pub fn alpha_mod(&mut self, alpha: u8) -> &mut Sprite {
+        self.alpha_mod = Some(alpha);
+        self
+    }
+
+    /// Sets the blend mode of the sprite; defaults to the blend mode of the texture.
+    pub fn blend_mode<B: Into<CustomBlendMode>>(&mut self, blend: B) -> &mut Sprite {
+        self.blend_mode = Some(blend.into());
+        self
+    }
+
+    /// Sets the layer of the sprite; defaults to 0.
+    ///
+    /// Sprites of lower layers are drawn first, whatever their texture.
+    pub fn layer(&mut self, layer: i32) -> &mut Sprite {
+        self.layer = layer;
+        self
+    }
+
+    fn is_transformed(&self) -> bool {
+        self.angle != 0.0 || self.flip_horizontal || self.flip_vertical
+    }
+}
+
+/// Statistics of a `SpriteBatch::flush`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct SpriteBatchStats {
+    /// The number of sprites drawn, i.e. of `SDL_RenderCopy` or `SDL_RenderCopyEx` calls.
+    pub draw_calls: usize,
+    /// The number of times the drawn texture changed.
+    pub texture_switches: usize,
+    /// The number of color mod, alpha mod and blend mode changes.
+    pub state_changes: usize,
+}
+
+struct BatchEntry {
+    texture: *mut sys::SDL_Texture,
+    renderer: Option<*mut sys::SDL_Renderer>,
+    sprite: Sprite,
+}
+
+/// The color mod, alpha mod and blend mode of a texture.
+#[derive(Copy, Clone, PartialEq)]
+struct TextureState {
+    color_mod: (u8, u8, u8),
+    alpha_mod: u8,
+    blend_mode: CustomBlendMode,
+}
+
+impl TextureState {
+    fn of(texture: *mut sys::SDL_Texture) -> TextureState {
+        let texture = InternalTexture { raw: texture };
+        TextureState {
+            color_mod: texture.color_mod(),
+            alpha_mod: texture.alpha_mod(),
+            blend_mode: texture.custom_blend_mode(),
+        }
+    }
+
+    /// Applies the differences between `self` and `current`, returning how many there were.
+    fn apply(&self, texture: *mut sys::SDL_Texture, current: &TextureState) -> Result<usize, Error> {
+        let mut texture = InternalTexture { raw: texture };
+        let mut changes = 0;
+        if self.color_mod != current.color_mod {
+            let (r, g, b) = self.color_mod;
+            texture.set_color_mod(r, g, b);
+            changes += 1;
+        }
+        if self.alpha_mod != current.alpha_mod {
+            texture.set_alpha_mod(self.alpha_mod);
+            changes += 1;
+        }
+        if self.blend_mode != current.blend_mode {
+            texture.try_set_blend_mode(self.blend_mode)?;
+            changes += 1;
+        }
+        Ok(changes)
+    }
+}
+
+/// Accumulates sprites, and draws them at once, optionally grouped to minimise texture and state
+/// changes.
+///
+/// Sprites are drawn by layer, and in the order they were added within a layer. With
+/// `set_grouping(true)`, the sprites of a layer are also grouped by texture, in the order each
+/// texture was first added, then by blend mode. This changes which of two overlapping sprites
+/// with different textures is drawn on top, so only enable it when they do not overlap.
+///
+/// The color mod, alpha mod and blend mode of the textures are restored after each `flush`.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::rect::Rect;
+/// use sdl2::render::SpriteBatch;
+///
+/// # fn draw(canvas: &mut sdl2::render::WindowCanvas, tiles: &sdl2::render::Texture,
+/// #         player: &sdl2::render::Texture) -> Result<(), sdl2::Error> {
+/// let mut batch = SpriteBatch::new();
+/// for x in 0..20 {
+///     batch.add(tiles, Rect::new(0, 0, 16, 16), Rect::new(x * 16, 0, 16, 16));
+/// }
+/// batch.add(player, None, Rect::new(40, 0, 16, 16))
+///     .layer(1)
+///     .angle(90.0)
+///     .alpha_mod(128);
+/// let stats = batch.flush(canvas)?;
+/// assert_eq!(stats.texture_switches, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SpriteBatch<'t> {
+    entries: Vec<BatchEntry>,
+    grouping: bool,
+    _marker: PhantomData<&'t ()>,
+}
+
+impl<'t> Default for SpriteBatch<'t> {
+    fn default() -> SpriteBatch<'t> {
+        SpriteBatch::new()
+    }
+}
+
+impl<'t> SpriteBatch<'t> {
+    pub fn new() -> SpriteBatch<'t> {
+        SpriteBatch::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> SpriteBatch<'t> {
+        SpriteBatch {
+            entries: Vec::with_capacity(capacity),
+            grouping: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets whether the sprites of a layer are grouped by texture and blend mode; defaults to
+    /// false.
+    pub fn set_grouping(&mut self, grouping: bool) {
+        self.grouping = grouping;
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all the sprites without drawing them.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Adds a sprite, copying `src` of `texture` to `dst` like `Canvas::copy`.
+    ///
+    /// Returns the sprite, to customize it.
+    pub fn add<X, R1, R2>(&mut self, texture: &'t X, src: R1, dst: R2) -> &mut Sprite
+        where X: CopySource + ?Sized,
+              R1: Into<Option<Rect>>,
+              R2: Into<Option<Rect>>
+    {
+        self.entries.push(BatchEntry {
+            texture: texture.texture_raw(),
+            renderer: texture.renderer_raw(),
+            sprite: Sprite {
+                src: src.into(),
+                dst: dst.into(),
+                angle: 0.0,
+                center: None,
+                flip_horizontal: false,
+                flip_vertical: false,
+                color_mod: None,
+                alpha_mod: None,
+                blend_mode: None,
+                layer: 0,
+            },
+        });
+        &mut self.entries.last_mut().unwrap().sprite
+    }
+
+    fn sort(&mut self) {
+        // `sort_by_key` is stable, so sprites with the same key keep the order they were added in
+        if self.grouping {
+            // Textures are ordered by their first sprite rather than by address, so that the
+            // drawing order does not depend on where they were allocated
+            let mut first_use = HashMap::new();
+            for (index, entry) in self.entries.iter().enumerate() {
+                first_use.entry(entry.texture).or_insert(index);
+            }
+            self.entries.sort_by_key(|entry| {
+                let blend_mode = entry.sprite.blend_mode.map(|mode| mode.raw());
+                (entry.sprite.layer, first_use[&entry.texture], blend_mode)
+            });
+        } else {
+            self.entries.sort_by_key(|entry| entry.sprite.layer);
+        }
+    }
+
+    /// Draws all the sprites on `canvas`, and empties the batch.
+    ///
+    /// Errors if a sprite does not belong to the renderer of `canvas` (see `SharedTexture`), or
+    /// if drawing fails; the sprites are discarded in both cases.
+    pub fn flush<T: RenderTarget>(&mut self, canvas: &mut Canvas<T>) -> Result<SpriteBatchStats, Error> {
+        self.sort();
+
+        let renderer = canvas.context.raw;
+        if self.entries.iter().any(|e| e.renderer.map(|r| r != renderer) == Some(true)) {
+            self.entries.clear();
+            return Err(Error::SdlError("Texture was not created with this renderer".to_owned()));
+        }
+
+        let mut stats = SpriteBatchStats::default();
+        // The original and current state of every texture used
+        let mut states: Vec<(*mut sys::SDL_Texture, TextureState, TextureState)> = Vec::new();
+        let mut current_texture = ptr::null_mut();
+        let mut result = Ok(());
+        for entry in &self.entries {
+            if entry.texture != current_texture {
+                current_texture = entry.texture;
+                stats.texture_switches += 1;
+            }
+            let index = match states.iter().position(|&(texture, _, _)| texture == entry.texture) {
+                Some(index) => index,
+                None => {
+                    let state = TextureState::of(entry.texture);
+                    states.push((entry.texture, state, state));
+                    states.len() - 1
+                },
+            };
+            let (_, original, current) = states[index];
+            let sprite = &entry.sprite;
+            let wanted = TextureState {
+                color_mod: sprite.color_mod.unwrap_or(original.color_mod),
+                alpha_mod: sprite.alpha_mod.unwrap_or(original.alpha_mod),
+                blend_mode: sprite.blend_mode.unwrap_or(original.blend_mode),
+            };
+            match wanted.apply(entry.texture, &current) {
+                Ok(changes) => stats.state_changes += changes,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                },
+            }
+            states[index].2 = wanted;
+
+            let src = sprite.src.as_ref().map_or(ptr::null(), |rect| rect.raw());
+            let dst = sprite.dst.as_ref().map_or(ptr::null(), |rect| rect.raw());
+            let ret = unsafe {
+                if sprite.is_transformed() {
+                    use crate::sys::SDL_RendererFlip::*;
+                    let flip = match (sprite.flip_horizontal, sprite.flip_vertical) {
+                        (false, false) => SDL_FLIP_NONE,
+                        (true, false) => SDL_FLIP_HORIZONTAL,
+                        (false, true) => SDL_FLIP_VERTICAL,
+                        (true, true) => transmute::<u32, sys::SDL_RendererFlip>(
+                                        SDL_FLIP_HORIZONTAL as u32 | SDL_FLIP_VERTICAL as u32),
+                    };
+                    let center = sprite.center.as_ref().map_or(ptr::null(), |point| point.raw());
+                    sys::SDL_RenderCopyEx(renderer, entry.texture, src, dst,
+                                         sprite.angle as c_double, center, flip)
+                } else {
+                    sys::SDL_RenderCopy(renderer, entry.texture, src, dst)
+                }
+            };
+            if ret != 0 {
+                result = Err(get_error_as_error());
+                break;
+            }
+            stats.draw_calls += 1;
+        }
+
+        for &(texture, original, current) in &states {
+            // Restoring a state the texture had before cannot fail
+            let _ = original.apply(texture, &current);
+        }
+        self.entries.clear();
+        result.map(|()| stats)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SpriteBatch;
+    use crate::render::{BlendMode, CopySource, CustomBlendMode};
+    use crate::sys;
+
+    /// A texture pointer which is only sorted, never drawn.
+    struct FakeTexture(usize);
+
+    unsafe impl CopySource for FakeTexture {
+        fn texture_raw(&self) -> *mut sys::SDL_Texture {
+            self.0 as *mut sys::SDL_Texture
+        }
+    }
+
+    fn sorted_sprites(grouping: bool) -> Vec<(i32, usize, Option<CustomBlendMode>, Option<u8>)> {
+        let (a, b) = (FakeTexture(0x10), FakeTexture(0x20));
+        let mut batch = SpriteBatch::new();
+        batch.set_grouping(grouping);
+        batch.add(&b, None, None);
+        batch.add(&a, None, None).blend_mode(BlendMode::Add);
+        batch.add(&b, None, None).layer(-1);
+        batch.add(&a, None, None);
+        batch.add(&b, None, None).alpha_mod(1);
+        batch.sort();
+
+        batch.entries.iter()
+            .map(|e| (e.sprite.layer, e.texture as usize, e.sprite.blend_mode, e.sprite.alpha_mod))
+            .collect()
+    }
+
+    #[test]
+    fn sprites_sorted_by_layer() {
+        assert_eq!(sorted_sprites(false), vec![
+            (-1, 0x20, None, None),
+            (0, 0x20, None, None),
+            (0, 0x10, Some(BlendMode::Add.into()), None),
+            (0, 0x10, None, None),
+            (0, 0x20, None, Some(1)),
+        ]);
+    }
+
+    #[test]
+    fn sprites_grouped_by_texture_and_blend_mode() {
+        // `b` comes first, since its first sprite was added before the first sprite of `a`
+        assert_eq!(sorted_sprites(true), vec![
+            (-1, 0x20, None, None),
+            (0, 0x20, None, None),
+            (0, 0x20, None, Some(1)),
+            (0, 0x10, None, None),
+            (0, 0x10, Some(BlendMode::Add.into()), None),
+        ]);
+    }
+}
@@ -0,0 +1,492 @@
+use crate::Error;
+use crate::event::Event;
+use crate::rect::{self, Point, Rect};
+use super::{Canvas, CopySource, RenderTarget};
+
+/// A 2D camera, mapping world coordinates to screen coordinates.
+///
+/// A world point is moved so that `position` is at the origin, rotated clockwise by `rotation`
+/// degrees, multiplied by `zoom` and finally moved to `origin`, in screen coordinates. The
+/// default camera is the identity.
+///
+/// Screen coordinates are the coordinates used to draw on the `Canvas`, which are also the
+/// coordinates of mouse events when the canvas has no viewport, scale or logical size set.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Camera {
+    /// The world point shown at `origin`.
+    pub position: (f64, f64),
+    /// The scale factor from world to screen sizes. Must be finite and positive, otherwise
+    /// drawing through the camera fails.
+    pub zoom: f64,
+    /// The clockwise rotation of the world on screen, in degrees.
+    pub rotation: f64,
+    /// The screen point showing `position`, which is also the center of rotation and zoom.
+    pub origin: (f64, f64),
+}
+
+impl Default for Camera {
+    fn default() -> Camera {
+        Camera { position: (0.0, 0.0), zoom: 1.0, rotation: 0.0, origin: (0.0, 0.0) }
+    }
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        Camera::default()
+    }
+
+    /// Returns a camera showing `position` at the center of a viewport of the given size.
+    pub fn centered(position: (f64, f64), viewport_size: (u32, u32)) -> Camera {
+        Camera {
+            position,
+            origin: (f64::from(viewport_size.0) / 2.0, f64::from(viewport_size.1) / 2.0),
+            ..Camera::default()
+        }
+    }
+
+    fn sin_cos(&self) -> (f64, f64) {
+        self.rotation.to_radians().sin_cos()
+    }
+
+    /// Converts a point from world to screen coordinates.
+    pub fn world_to_screen(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (sin, cos) = self.sin_cos();
+        let (x, y) = ((x - self.position.0) * self.zoom, (y - self.position.1) * self.zoom);
+        (x * cos - y * sin + self.origin.0, x * sin + y * cos + self.origin.1)
+    }
+
+    /// Converts a point from screen to world coordinates.
+    pub fn screen_to_world(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (x, y) = self.screen_vector_to_world((x - self.origin.0, y - self.origin.1));
+        (x + self.position.0, y + self.position.1)
+    }
+
+    /// Converts a displacement (like a relative mouse motion) from screen to world coordinates.
+    fn screen_vector_to_world(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (sin, cos) = self.sin_cos();
+        ((x * cos + y * sin) / self.zoom, (y * cos - x * sin) / self.zoom)
+    }
+
+    /// Converts a point from world to screen coordinates, rounded to the nearest pixel.
+    pub fn world_to_screen_point<P: Into<Point>>(&self, point: P) -> Point {
+        let point = point.into();
+        round_point(self.world_to_screen((f64::from(point.x()), f64::from(point.y()))))
+    }
+
+    /// Converts a point from screen to world coordinates, rounded to the nearest integer.
+    pub fn screen_to_world_point<P: Into<Point>>(&self, point: P) -> Point {
+        let point = point.into();
+        round_point(self.screen_to_world((f64::from(point.x()), f64::from(point.y()))))
+    }
+
+    /// Converts the coordinates of a mouse motion or mouse button event to world coordinates.
+    ///
+    /// The relative motion of `MouseMotion` events is rotated and scaled too, so small motions
+    /// may be rounded to 0 when zoomed in. Returns `None` for other events.
+    pub fn event_to_world(&self, event: &Event) -> Option<Event> {
+        match *event {
+            Event::MouseMotion { timestamp, window_id, which, mousestate, x, y, xrel, yrel } => {
+                let position = self.screen_to_world_point((x, y));
+                let relative = round_point(
+                    self.screen_vector_to_world((f64::from(xrel), f64::from(yrel))));
+                Some(Event::MouseMotion {
+                    timestamp,
+                    window_id,
+                    which,
+                    mousestate,
+                    x: position.x(),
+                    y: position.y(),
+                    xrel: relative.x(),
+                    yrel: relative.y(),
+                })
+            },
+            Event::MouseButtonDown { timestamp, window_id, which, mouse_btn, clicks, x, y } => {
+                let position = self.screen_to_world_point((x, y));
+                Some(Event::MouseButtonDown {
+                    timestamp, window_id, which, mouse_btn, clicks, x: position.x(), y: position.y(),
+                })
+            },
+            Event::MouseButtonUp { timestamp, window_id, which, mouse_btn, clicks, x, y } => {
+                let position = self.screen_to_world_point((x, y));
+                Some(Event::MouseButtonUp {
+                    timestamp, window_id, which, mouse_btn, clicks, x: position.x(), y: position.y(),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the rotation is a multiple of 90 degrees, so that rectangles stay
+    /// aligned with the screen axes.
+    fn is_axis_aligned(&self) -> bool {
+        let (sin, cos) = self.sin_cos();
+        sin.abs() < 1e-9 || cos.abs() < 1e-9
+    }
+
+    /// Returns the screen coordinates of the corners of a world rectangle, clockwise from its
+    /// top-left corner.
+    fn rect_to_screen(&self, rect: Rect) -> [(f64, f64); 4] {
+        let (left, top) = (f64::from(rect.left()), f64::from(rect.top()));
+        let (right, bottom) = (f64::from(rect.right()), f64::from(rect.bottom()));
+        [
+            self.world_to_screen((left, top)),
+            self.world_to_screen((right, top)),
+            self.world_to_screen((right, bottom)),
+            self.world_to_screen((left, bottom)),
+        ]
+    }
+}
+
+fn round_point((x, y): (f64, f64)) -> Point {
+    Point::new(x.round() as i32, y.round() as i32)
+}
+
+/// The bounding box of some points, as `(left, top, right, bottom)`.
+fn bounds(points: &[(f64, f64)]) -> (f64, f64, f64, f64) {
+    points.iter().fold(
+        (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        |(left, top, right, bottom), &(x, y)| (left.min(x), top.min(y), right.max(x), bottom.max(y)))
+}
+
+/// Clamps a coordinate to a range small enough for the distance between two clamped coordinates
+/// to fit in the size of a `Rect`.
+fn clamp_coordinate(value: f64) -> i32 {
+    let limit = f64::from(rect::max_int_value() / 2);
+    value.max(-limit).min(limit) as i32
+}
+
+/// Rounds a bounding box to a rectangle, or returns `None` if it is empty.
+///
+/// Bounding boxes too large for a `Rect`, e.g. with a huge zoom, are clamped.
+fn bounds_to_rect((left, top, right, bottom): (f64, f64, f64, f64)) -> Option<Rect> {
+    let (left, top) = (clamp_coordinate(left.round()), clamp_coordinate(top.round()));
+    let (right, bottom) = (clamp_coordinate(right.round()), clamp_coordinate(bottom.round()));
+    if right > left && bottom > top {
+        Some(Rect::new(left, top, (right - left) as u32, (bottom - top) as u32))
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if a bounding box overlaps the area from `(0, 0)` to `size`.
+fn overlaps_area((left, top, right, bottom): (f64, f64, f64, f64), size: (u32, u32)) -> bool {
+    right >= 0.0 && bottom >= 0.0 && left <= f64::from(size.0) && top <= f64::from(size.1)
+}
+
+/// Computes the horizontal spans of pixels whose center is inside a convex polygon, as
+/// `(y, first_x, last_x)`, keeping only the pixels in the area from `(0, 0)` to `size`.
+fn convex_spans(polygon: &[(f64, f64)], size: (u32, u32)) -> Vec<(i32, i32, i32)> {
+    let mut spans = Vec::new();
+    if size.0 == 0 || size.1 == 0 {
+        return spans;
+    }
+    // Clamping in floating point keeps the pixel coordinates within `i32`, whatever the polygon.
+    let (max_x, max_y) = (f64::from(size.0 - 1), f64::from(size.1 - 1));
+    let (_, top, _, bottom) = bounds(polygon);
+    let first_row = (top - 0.5).ceil().max(0.0);
+    let last_row = (bottom - 0.5).floor().min(max_y);
+    if first_row > last_row {
+        return spans;
+    }
+    for y in first_row as i32..=last_row as i32 {
+        let center = f64::from(y) + 0.5;
+        let (mut left, mut right) = (f64::INFINITY, f64::NEG_INFINITY);
+        for (i, &(x1, y1)) in polygon.iter().enumerate() {
+            let (x2, y2) = polygon[(i + 1) % polygon.len()];
+            if (y1 <= center && center < y2) || (y2 <= center && center < y1) {
+                let x = x1 + (center - y1) * (x2 - x1) / (y2 - y1);
+                left = left.min(x);
+                right = right.max(x);
+            }
+        }
+        let first = (left - 0.5).ceil().max(0.0);
+        let last = (right - 0.5).floor().min(max_x);
+        if first <= last {
+            spans.push((y, first as i32, last as i32));
+        }
+    }
+    spans
+}
+
+/// A `Canvas` drawing through a `Camera`.
+///
+/// The drawing methods take world coordinates, and skip shapes that are entirely outside of the
+/// viewport. Use `canvas()` to draw in screen coordinates, e.g. for a user interface.
+///
+/// ```rust,no_run
+/// # use sdl2::render::{Camera, Canvas, TransformedCanvas};
+/// # use sdl2::video::Window;
+/// # use sdl2::rect::Rect;
+/// # let mut canvas : Canvas<Window> = unimplemented!();
+/// let mut camera = Camera::centered((100.0, 100.0), canvas.output_size().unwrap());
+/// camera.zoom = 2.0;
+/// camera.rotation = 15.0;
+/// let mut world = TransformedCanvas::new(&mut canvas, camera);
+/// world.fill_rect(Rect::new(90, 90, 20, 20)).unwrap();
+/// ```
+pub struct TransformedCanvas<'c, T: RenderTarget + 'c> {
+    canvas: &'c mut Canvas<T>,
+    camera: Camera,
+}
+
+impl<'c, T: RenderTarget> TransformedCanvas<'c, T> {
+    pub fn new(canvas: &'c mut Canvas<T>, camera: Camera) -> TransformedCanvas<'c, T> {
+        TransformedCanvas { canvas, camera }
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
+
+    /// Returns the underlying canvas, which draws in screen coordinates.
+    pub fn canvas(&mut self) -> &mut Canvas<T> {
+        self.canvas
+    }
+
+    /// Fails if the camera's zoom cannot map world to screen coordinates.
+    fn check_camera(&self) -> Result<(), Error> {
+        let zoom = self.camera.zoom;
+        if zoom.is_finite() && zoom > 0.0 {
+            Ok(())
+        } else {
+            Err(Error::SdlError(format!("Invalid camera zoom: {}", zoom)))
+        }
+    }
+
+    fn viewport_size(&self) -> (u32, u32) {
+        self.canvas.viewport().size()
+    }
+
+    /// Returns the smallest world rectangle containing everything visible in the viewport.
+    ///
+    /// The rectangle is clamped to the coordinates a `Rect` can hold when zoomed out very far.
+    pub fn visible_world_rect(&self) -> Result<Rect, Error> {
+        self.check_camera()?;
+        let (w, h) = self.viewport_size();
+        let (w, h) = (f64::from(w), f64::from(h));
+        let corners = [
+            self.camera.screen_to_world((0.0, 0.0)),
+            self.camera.screen_to_world((w, 0.0)),
+            self.camera.screen_to_world((w, h)),
+            self.camera.screen_to_world((0.0, h)),
+        ];
+        let (left, top, right, bottom) = bounds(&corners);
+        let (left, top) = (clamp_coordinate(left.floor()), clamp_coordinate(top.floor()));
+        let (right, bottom) = (clamp_coordinate(right.ceil()), clamp_coordinate(bottom.ceil()));
+        Ok(Rect::new(left, top, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    /// Returns `true` if some part of the world rectangle may be visible in the viewport.
+    pub fn is_visible(&self, rect: Rect) -> bool {
+        overlaps_area(bounds(&self.camera.rect_to_screen(rect)), self.viewport_size())
+    }
+
+    /// Draws a point, given in world coordinates.
+    pub fn draw_point<P: Into<Point>>(&mut self, point: P) -> Result<(), Error> {
+        self.check_camera()?;
+        let point = self.camera.world_to_screen_point(point);
+        let (x, y) = (f64::from(point.x()), f64::from(point.y()));
+        if !overlaps_area((x, y, x, y), self.viewport_size()) {
+            return Ok(());
+        }
+        self.canvas.draw_point(point)
+    }
+
+    /// Draws a line, given in world coordinates.
+    pub fn draw_line<P1: Into<Point>, P2: Into<Point>>(&mut self, start: P1, end: P2)
+                                                       -> Result<(), Error> {
+        self.check_camera()?;
+        let (start, end) = (self.camera.world_to_screen_point(start),
+                            self.camera.world_to_screen_point(end));
+        let points = [(f64::from(start.x()), f64::from(start.y())),
+                      (f64::from(end.x()), f64::from(end.y()))];
+        if !overlaps_area(bounds(&points), self.viewport_size()) {
+            return Ok(());
+        }
+        self.canvas.draw_line(start, end)
+    }
+
+    /// Draws the outline of a rectangle, given in world coordinates.
+    pub fn draw_rect(&mut self, rect: Rect) -> Result<(), Error> {
+        self.check_camera()?;
+        let corners = self.camera.rect_to_screen(rect);
+        let bounds = bounds(&corners);
+        if !overlaps_area(bounds, self.viewport_size()) {
+            return Ok(());
+        }
+        if self.camera.is_axis_aligned() {
+            match bounds_to_rect(bounds) {
+                Some(rect) => self.canvas.draw_rect(rect),
+                None => Ok(()),
+            }
+        } else {
+            let points: Vec<Point> = corners.iter().chain(corners.first())
+                .map(|&corner| round_point(corner))
+                .collect();
+            self.canvas.draw_lines(&points[..])
+        }
+    }
+
+    /// Fills a rectangle, given in world coordinates.
+    pub fn fill_rect(&mut self, rect: Rect) -> Result<(), Error> {
+        self.check_camera()?;
+        let corners = self.camera.rect_to_screen(rect);
+        let bounds = bounds(&corners);
+        if !overlaps_area(bounds, self.viewport_size()) {
+            return Ok(());
+        }
+        if self.camera.is_axis_aligned() {
+            match bounds_to_rect(bounds) {
+                Some(rect) => self.canvas.fill_rect(rect),
+                None => Ok(()),
+            }
+        } else {
+            // The renderer cannot fill arbitrary polygons, so the rotated rectangle is filled
+            // with one rectangle per row of pixels.
+            let rows: Vec<Rect> = convex_spans(&corners, self.viewport_size()).into_iter()
+                .map(|(y, first, last)| Rect::new(first, y, (last - first) as u32 + 1, 1))
+                .collect();
+            self.canvas.fill_rects(&rows)
+        }
+    }
+
+    /// Copies a portion of the texture to a rectangle given in world coordinates.
+    ///
+    /// * If `src` is `None`, the entire texture is copied.
+    pub fn copy<X, R>(&mut self, texture: &X, src: R, dst: Rect) -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R: Into<Option<Rect>>
+    {
+        self.copy_ex(texture, src, dst, 0.0, false, false)
+    }
+
+    /// Copies a portion of the texture to a rectangle given in world coordinates, rotated
+    /// clockwise by `angle` degrees around its center and optionally flipped.
+    ///
+    /// * If `src` is `None`, the entire texture is copied.
+    pub fn copy_ex<X, R>(&mut self,
+                         texture: &X,
+                         src: R,
+                         dst: Rect,
+                         angle: f64,
+                         flip_horizontal: bool,
+                         flip_vertical: bool)
+                         -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R: Into<Option<Rect>>
+    {
+        self.check_camera()?;
+        let (center_x, center_y) = self.camera.world_to_screen((
+            f64::from(dst.x()) + f64::from(dst.width()) / 2.0,
+            f64::from(dst.y()) + f64::from(dst.height()) / 2.0,
+        ));
+        let width = f64::from(dst.width()) * self.camera.zoom;
+        let height = f64::from(dst.height()) * self.camera.zoom;
+
+        // Any rotation of the rectangle stays within the circle around its corners.
+        let radius = width.hypot(height) / 2.0;
+        let circle_bounds = (center_x - radius, center_y - radius, center_x + radius, center_y + radius);
+        if !overlaps_area(circle_bounds, self.viewport_size()) {
+            return Ok(());
+        }
+
+        let screen_dst = match bounds_to_rect((center_x - width / 2.0, center_y - height / 2.0,
+                                               center_x + width / 2.0, center_y + height / 2.0)) {
+            Some(rect) => rect,
+            None => return Ok(()),
+        };
+        let angle = angle + self.camera.rotation;
+        if angle == 0.0 && !flip_horizontal && !flip_vertical {
+            self.canvas.copy(texture, src, screen_dst)
+        } else {
+            self.canvas.copy_ex(texture, src, screen_dst, angle, None, flip_horizontal, flip_vertical)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bounds_to_rect, convex_spans, Camera};
+    use crate::event::Event;
+    use crate::rect::{self, Point, Rect};
+
+    fn assert_close((x1, y1): (f64, f64), (x2, y2): (f64, f64)) {
+        assert!((x1 - x2).abs() < 1e-9 && (y1 - y2).abs() < 1e-9,
+                "({}, {}) != ({}, {})", x1, y1, x2, y2);
+    }
+
+    #[test]
+    fn camera_round_trip() {
+        let camera = Camera { position: (10.0, 20.0), zoom: 2.0, rotation: 90.0, origin: (50.0, 50.0) };
+        assert_close(camera.world_to_screen((10.0, 20.0)), (50.0, 50.0));
+        // rotating by 90 degrees clockwise moves +x to +y on screen
+        assert_close(camera.world_to_screen((11.0, 20.0)), (50.0, 52.0));
+        assert_close(camera.screen_to_world(camera.world_to_screen((-3.5, 7.25))), (-3.5, 7.25));
+
+        let camera = Camera::centered((0.0, 0.0), (640, 480));
+        assert_eq!(camera.world_to_screen_point((10, -10)), Point::new(330, 230));
+        assert_eq!(camera.screen_to_world_point((330, 230)), Point::new(10, -10));
+    }
+
+    #[test]
+    fn camera_mouse_events() {
+        let camera = Camera { position: (0.0, 0.0), zoom: 2.0, rotation: 0.0, origin: (100.0, 100.0) };
+        let motion = Event::MouseMotion {
+            timestamp: 0, window_id: 1, which: 0,
+            mousestate: crate::mouse::MouseState::from_sdl_state(0),
+            x: 120, y: 90, xrel: 4, yrel: -6,
+        };
+        match camera.event_to_world(&motion) {
+            Some(Event::MouseMotion { x, y, xrel, yrel, .. }) => {
+                assert_eq!((x, y, xrel, yrel), (10, -5, 2, -3));
+            },
+            _ => panic!("expected a MouseMotion event"),
+        }
+        assert!(camera.event_to_world(&Event::Quit { timestamp: 0 }).is_none());
+    }
+
+    #[test]
+    fn camera_spans() {
+        // an axis-aligned square covers the pixels whose center is inside
+        let square = [(1.0, 1.0), (4.0, 1.0), (4.0, 3.0), (1.0, 3.0)];
+        assert_eq!(convex_spans(&square, (10, 10)), vec![(1, 1, 3), (2, 1, 3)]);
+
+        // a diamond gets wider toward its middle
+        let diamond = [(5.0, 0.0), (10.0, 5.0), (5.0, 10.0), (0.0, 5.0)];
+        let spans = convex_spans(&diamond, (10, 10));
+        assert_eq!(spans.len(), 10);
+        assert_eq!(spans[0], (0, 4, 5));
+        assert_eq!(spans[4], (4, 0, 9));
+
+        // spans are clipped to the area, even for a huge polygon
+        let spans = convex_spans(&diamond, (6, 3));
+        assert_eq!(spans, vec![(0, 4, 5), (1, 3, 5), (2, 2, 5)]);
+        let huge = [(-1e12, -1e12), (1e12, -1e12), (1e12, 1e12), (-1e12, 1e12)];
+        assert_eq!(convex_spans(&huge, (4, 2)), vec![(0, 0, 3), (1, 0, 3)]);
+        assert!(convex_spans(&diamond, (0, 10)).is_empty());
+    }
+
+    #[test]
+    fn camera_huge_bounds() {
+        assert_eq!(bounds_to_rect((0.4, 1.0, 3.6, 2.4)), Some(Rect::new(0, 1, 4, 1)));
+        assert_eq!(bounds_to_rect((2.0, 2.0, 2.0, 5.0)), None);
+        // bounds past the range of `i32` are clamped instead of overflowing
+        let limit = (rect::max_int_value() / 2) as i32;
+        let huge = bounds_to_rect((-1e12, -1e12, 1e12, 1e12)).unwrap();
+        assert_eq!((huge.left(), huge.right()), (-limit, limit));
+        assert_eq!(huge.height(), 2 * limit as u32);
+
+        let camera = Camera { rotation: 45.0, ..Camera::default() };
+        assert!(!camera.is_axis_aligned());
+        let corners = camera.rect_to_screen(Rect::new(0, 0, 10, 10));
+        assert_close(corners[2], (0.0, 200f64.sqrt()));
+    }
+}
@@ -0,0 +1,222 @@
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::mem;
+use std::slice;
+
+use crate::Error;
+use crate::pixels::{Color, PixelFormat, PixelFormatEnum};
+use crate::rect::Rect;
+use super::InternalTexture;
+
+/// A type which can hold one pixel of a locked texture, for `Texture::with_lock_typed`.
+///
+/// It is implemented for `u8`, `u16`, `u32` and `[u8; 3]`, matching the formats with 1, 2, 4 and
+/// 3 bytes per pixel.
+///
+/// # Safety
+/// Implementors must be plain data types, valid for any bit pattern.
+pub unsafe trait LockPixel: Copy + 'static {}
+
+unsafe impl LockPixel for u8 {}
+unsafe impl LockPixel for u16 {}
+unsafe impl LockPixel for u32 {}
+unsafe impl LockPixel for [u8; 3] {}
+
+/// Typed access to the pixels of a locked texture, given by `Texture::with_lock_typed`.
+///
+/// Rows are exposed as slices of exactly `width()` pixels, without the padding at the end of
+/// each row.
+pub struct TextureLock<'a, P: LockPixel> {
+    pixels: &'a mut [u8],
+    pitch: usize,
+    width: usize,
+    height: usize,
+    format: PixelFormatEnum,
+    pixel_format: PixelFormat,
+    _pixel: PhantomData<P>,
+}
+
+/// The number of bytes per pixel of a non-YUV format, or 0 if a pixel is smaller than a byte.
+fn bytes_per_pixel(format: PixelFormatEnum) -> usize {
+    (format as u32 & 0xFF) as usize
+}
+
+impl<'a, P: LockPixel> TextureLock<'a, P> {
+    fn new(pixels: &'a mut [u8], pitch: usize, size: (u32, u32), format: PixelFormatEnum)
+           -> Result<TextureLock<'a, P>, Error> {
+        if format.is_fourcc() || bytes_per_pixel(format) != mem::size_of::<P>() {
+            return Err(Error::SdlError(format!(
+                "A {:?} texture cannot be locked with pixels of {} bytes",
+                format, mem::size_of::<P>())));
+        }
+        let align = mem::align_of::<P>();
+        if (pixels.as_ptr() as usize | pitch) & (align - 1) != 0 {
+            return Err(Error::SdlError("The locked pixels are not aligned".to_owned()));
+        }
+        let pixel_format = PixelFormat::try_from(format).map_err(Error::SdlError)?;
+        Ok(TextureLock {
+            pixels,
+            pitch,
+            width: size.0 as usize,
+            height: size.1 as usize,
+            format,
+            pixel_format,
+            _pixel: PhantomData,
+        })
+    }
+
+    /// The width of the locked area, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the locked area, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The length of a row, in bytes, including padding.
+    pub fn pitch(&self) -> usize {
+        self.pitch
+    }
+
+    pub fn format(&self) -> PixelFormatEnum {
+        self.format
+    }
+
+    /// Returns row `y` of the locked area.
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[P] {
+        assert!(y < self.height, "row {} is out of bounds", y);
+        cast_row(&self.pixels[y * self.pitch..], self.width)
+    }
+
+    /// Returns row `y` of the locked area.
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [P] {
+        assert!(y < self.height, "row {} is out of bounds", y);
+        cast_row_mut(&mut self.pixels[y * self.pitch..], self.width)
+    }
+
+    /// Iterates over the rows of the locked area, from top to bottom.
+    pub fn rows(&self) -> TextureLockRows<'_, P> {
+        TextureLockRows {
+            rows: self.pixels.chunks(self.pitch).take(self.height),
+            width: self.width,
+            _pixel: PhantomData,
+        }
+    }
+
+    /// Iterates over the rows of the locked area, from top to bottom.
+    pub fn rows_mut(&mut self) -> TextureLockRowsMut<'_, P> {
+        TextureLockRowsMut {
+            rows: self.pixels.chunks_mut(self.pitch).take(self.height),
+            width: self.width,
+            _pixel: PhantomData,
+        }
+    }
+
+    /// Sets the pixel at `(x, y)` to the color, converted to the format of the texture.
+    ///
+    /// # Panics
+    /// Panics if `(x, y)` is out of bounds.
+    pub fn put_pixel(&mut self, x: usize, y: usize, color: Color) {
+        assert!(x < self.width && y < self.height, "pixel ({}, {}) is out of bounds", x, y);
+        let size = mem::size_of::<P>();
+        let value = color.to_u32(&self.pixel_format).to_ne_bytes();
+        // SDL stores the pixels of 1, 2 and 3 bytes in the low-order bytes of the mapped value.
+        let value = if cfg!(target_endian = "big") { &value[4 - size..] } else { &value[..size] };
+        let offset = y * self.pitch + x * size;
+        self.pixels[offset..offset + size].copy_from_slice(value);
+    }
+}
+
+fn cast_row<P: LockPixel>(bytes: &[u8], width: usize) -> &[P] {
+    let bytes = &bytes[..width * mem::size_of::<P>()];
+    // The pointer and the pitch are checked to be aligned in `TextureLock::new`
+    unsafe { slice::from_raw_parts(bytes.as_ptr() as *const P, width) }
+}
+
+fn cast_row_mut<P: LockPixel>(bytes: &mut [u8], width: usize) -> &mut [P] {
+    let bytes = &mut bytes[..width * mem::size_of::<P>()];
+    unsafe { slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut P, width) }
+}
+
+/// An iterator over the rows of a `TextureLock`.
+pub struct TextureLockRows<'a, P> {
+    rows: ::std::iter::Take<slice::Chunks<'a, u8>>,
+    width: usize,
+    _pixel: PhantomData<P>,
+}
+
+impl<'a, P: LockPixel> Iterator for TextureLockRows<'a, P> {
+    type Item = &'a [P];
+
+    fn next(&mut self) -> Option<&'a [P]> {
+        let width = self.width;
+        self.rows.next().map(|row| cast_row(row, width))
+    }
+}
+
+/// A mutable iterator over the rows of a `TextureLock`.
+pub struct TextureLockRowsMut<'a, P> {
+    rows: ::std::iter::Take<slice::ChunksMut<'a, u8>>,
+    width: usize,
+    _pixel: PhantomData<P>,
+}
+
+impl<'a, P: LockPixel> Iterator for TextureLockRowsMut<'a, P> {
+    type Item = &'a mut [P];
+
+    fn next(&mut self) -> Option<&'a mut [P]> {
+        let width = self.width;
+        self.rows.next().map(|row| cast_row_mut(row, width))
+    }
+}
+
+impl InternalTexture {
+    pub fn with_lock_typed<P, F, R, R2>(&mut self, rect: R2, func: F) -> Result<R, Error>
+        where P: LockPixel,
+              F: FnOnce(&mut TextureLock<P>) -> R,
+              R2: Into<Option<Rect>>
+    {
+        let rect = rect.into();
+        let query = self.query();
+        let size = match rect {
+            Some(ref rect) => rect.size(),
+            None => (query.width, query.height),
+        };
+        self.with_lock(rect, |pixels, pitch| {
+            TextureLock::new(pixels, pitch, size, query.format).map(|mut lock| func(&mut lock))
+        })?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bytes_per_pixel, cast_row};
+    use crate::pixels::PixelFormatEnum;
+
+    #[test]
+    fn lock_fourcc_formats() {
+        assert!(PixelFormatEnum::YV12.is_fourcc());
+        assert!(PixelFormatEnum::YUY2.is_fourcc());
+        assert!(!PixelFormatEnum::ARGB8888.is_fourcc());
+        assert!(!PixelFormatEnum::Index8.is_fourcc());
+        assert!(!PixelFormatEnum::Unknown.is_fourcc());
+        assert_eq!(bytes_per_pixel(PixelFormatEnum::RGB24), 3);
+        assert_eq!(bytes_per_pixel(PixelFormatEnum::RGB565), 2);
+        assert_eq!(bytes_per_pixel(PixelFormatEnum::Index1LSB), 0);
+    }
+
+    #[test]
+    fn lock_row_skips_padding() {
+        let pixels = [1u8, 2, 3, 4, 5, 6, 0, 0];
+        let row: &[[u8; 3]] = cast_row(&pixels, 2);
+        assert_eq!(row, &[[1, 2, 3], [4, 5, 6]]);
+    }
+}
@@ -28,12 +28,26 @@
 //! None of the draw methods in `Canvas` are expected to fail.
 //! If they do, a panic is raised and the program is aborted.
 
+pub mod atlas;
+mod batch;
+mod camera;
+mod lock;
+mod target;
+
+pub use self::batch::{Sprite, SpriteBatch, SpriteBatchStats};
+pub use self::camera::{Camera, TransformedCanvas};
+pub use self::lock::{LockPixel, TextureLock, TextureLockRows, TextureLockRowsMut};
+pub use self::target::RenderTargetGuard;
+
 use crate::video::{Window, WindowContext};
+use crate::event::Event;
 use crate::surface;
 use crate::surface::{Surface, SurfaceRef, SurfaceContext};
 use crate::pixels;
 use crate::pixels::PixelFormatEnum;
 use crate::{Error, get_error, get_error_as_error};
+use std::any::Any;
+use std::cell::Cell;
 use std::fmt;
 use std::error::Error as StdError;
 #[cfg(not(feature = "unsafe_textures"))]
@@ -45,11 +59,13 @@ use std::rc::Rc;
 use libc::{c_int, c_double};
 use crate::rect::Point;
 use crate::rect::Rect;
+use crate::rect::{FPoint, FRect, RoundingMode};
 use std::ffi::CStr;
+use std::path::Path;
 use num::FromPrimitive;
 use std::vec::Vec;
 use crate::common::{validate_int, IntegerOrSdlError};
-use std::mem::{transmute, MaybeUninit};
+use std::mem::transmute;
 use libc::c_void;
 
 use crate::sys;
@@ -148,17 +164,18 @@ pub enum BlendMode {
 }
 
 impl FromPrimitive for BlendMode {
+    /// Returns `None` for the values of custom blend modes; see `CustomBlendMode`.
     fn from_i64(n: i64) -> Option<BlendMode> {
         use self::BlendMode::*;
-        let n = n as u32;
 
-        Some(match unsafe { transmute::<u32, SDL_BlendMode>(n) } {
-                 SDL_BlendMode::SDL_BLENDMODE_NONE => None,
-                 SDL_BlendMode::SDL_BLENDMODE_BLEND => Blend,
-                 SDL_BlendMode::SDL_BLENDMODE_ADD => Add,
-                 SDL_BlendMode::SDL_BLENDMODE_MOD => Mod,
-                 SDL_BlendMode::SDL_BLENDMODE_INVALID => Invalid,
-             })
+        match n {
+            x if x == None as i64 => Some(None),
+            x if x == Blend as i64 => Some(Blend),
+            x if x == Add as i64 => Some(Add),
+            x if x == Mod as i64 => Some(Mod),
+            x if x == Invalid as i64 => Some(Invalid),
+            _ => Option::None,
+        }
     }
 
     fn from_u64(n: u64) -> Option<BlendMode> {
@@ -166,6 +183,240 @@ impl FromPrimitive for BlendMode {
     }
 }
 
+impl From<BlendMode> for CustomBlendMode {
+    fn from(mode: BlendMode) -> CustomBlendMode {
+        CustomBlendMode { raw: mode as u32 }
+    }
+}
+
+/// The normalized factor used to multiply pixel components of a `CustomBlendMode`.
+#[repr(u32)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum BlendFactor {
+    /// 0, 0, 0, 0
+    Zero = sys::SDL_BlendFactor::SDL_BLENDFACTOR_ZERO as u32,
+    /// 1, 1, 1, 1
+    One = sys::SDL_BlendFactor::SDL_BLENDFACTOR_ONE as u32,
+    /// srcR, srcG, srcB, srcA
+    SrcColor = sys::SDL_BlendFactor::SDL_BLENDFACTOR_SRC_COLOR as u32,
+    /// 1-srcR, 1-srcG, 1-srcB, 1-srcA
+    OneMinusSrcColor = sys::SDL_BlendFactor::SDL_BLENDFACTOR_ONE_MINUS_SRC_COLOR as u32,
+    /// srcA, srcA, srcA, srcA
+    SrcAlpha = sys::SDL_BlendFactor::SDL_BLENDFACTOR_SRC_ALPHA as u32,
+    /// 1-srcA, 1-srcA, 1-srcA, 1-srcA
+    OneMinusSrcAlpha = sys::SDL_BlendFactor::SDL_BLENDFACTOR_ONE_MINUS_SRC_ALPHA as u32,
+    /// dstR, dstG, dstB, dstA
+    DstColor = sys::SDL_BlendFactor::SDL_BLENDFACTOR_DST_COLOR as u32,
+    /// 1-dstR, 1-dstG, 1-dstB, 1-dstA
+    OneMinusDstColor = sys::SDL_BlendFactor::SDL_BLENDFACTOR_ONE_MINUS_DST_COLOR as u32,
+    /// dstA, dstA, dstA, dstA
+    DstAlpha = sys::SDL_BlendFactor::SDL_BLENDFACTOR_DST_ALPHA as u32,
+    /// 1-dstA, 1-dstA, 1-dstA, 1-dstA
+    OneMinusDstAlpha = sys::SDL_BlendFactor::SDL_BLENDFACTOR_ONE_MINUS_DST_ALPHA as u32,
+}
+
+impl BlendFactor {
+    fn to_ll(self) -> sys::SDL_BlendFactor {
+        use self::BlendFactor::*;
+        use crate::sys::SDL_BlendFactor::*;
+
+        match self {
+            Zero => SDL_BLENDFACTOR_ZERO,
+            One => SDL_BLENDFACTOR_ONE,
+            SrcColor => SDL_BLENDFACTOR_SRC_COLOR,
+            OneMinusSrcColor => SDL_BLENDFACTOR_ONE_MINUS_SRC_COLOR,
+            SrcAlpha => SDL_BLENDFACTOR_SRC_ALPHA,
+            OneMinusSrcAlpha => SDL_BLENDFACTOR_ONE_MINUS_SRC_ALPHA,
+            DstColor => SDL_BLENDFACTOR_DST_COLOR,
+            OneMinusDstColor => SDL_BLENDFACTOR_ONE_MINUS_DST_COLOR,
+            DstAlpha => SDL_BLENDFACTOR_DST_ALPHA,
+            OneMinusDstAlpha => SDL_BLENDFACTOR_ONE_MINUS_DST_ALPHA,
+        }
+    }
+}
+
+/// The operation used to combine source and destination pixel components of a `CustomBlendMode`.
+///
+/// Only `Add` is supported by all renderers.
+#[repr(u32)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum BlendOperation {
+    /// dst + src
+    Add = sys::SDL_BlendOperation::SDL_BLENDOPERATION_ADD as u32,
+    /// dst - src
+    Subtract = sys::SDL_BlendOperation::SDL_BLENDOPERATION_SUBTRACT as u32,
+    /// src - dst
+    RevSubtract = sys::SDL_BlendOperation::SDL_BLENDOPERATION_REV_SUBTRACT as u32,
+    /// min(dst, src)
+    Minimum = sys::SDL_BlendOperation::SDL_BLENDOPERATION_MINIMUM as u32,
+    /// max(dst, src)
+    Maximum = sys::SDL_BlendOperation::SDL_BLENDOPERATION_MAXIMUM as u32,
+}
+
+impl BlendOperation {
+    fn to_ll(self) -> sys::SDL_BlendOperation {
+        use self::BlendOperation::*;
+        use crate::sys::SDL_BlendOperation::*;
+
+        match self {
+            Add => SDL_BLENDOPERATION_ADD,
+            Subtract => SDL_BLENDOPERATION_SUBTRACT,
+            RevSubtract => SDL_BLENDOPERATION_REV_SUBTRACT,
+            Minimum => SDL_BLENDOPERATION_MINIMUM,
+            Maximum => SDL_BLENDOPERATION_MAXIMUM,
+        }
+    }
+}
+
+/// A blend mode composed with `SDL_ComposeCustomBlendMode`; build it with `CustomBlendMode::builder()`.
+///
+/// Any `BlendMode` also converts into a `CustomBlendMode`, so that the `set_blend_mode` methods
+/// accept both, and `custom_blend_mode` returns the blend modes that `BlendMode` cannot
+/// represent.
+///
+/// Renderers do not support every combination of factors and operations: setting an unsupported
+/// mode fails, see `Canvas::try_set_blend_mode` and `Texture::try_set_blend_mode`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CustomBlendMode {
+    raw: u32,
+}
+
+impl CustomBlendMode {
+    /// Initializes a new `CustomBlendModeBuilder`.
+    pub fn builder() -> CustomBlendModeBuilder {
+        CustomBlendModeBuilder::new()
+    }
+
+    /// The `SDL_BlendMode` value of this blend mode.
+    pub fn raw(&self) -> u32 {
+        self.raw
+    }
+
+    /// Returns the equivalent `BlendMode`, or `None` if it is not one of the predefined modes.
+    pub fn to_blend_mode(&self) -> Option<BlendMode> {
+        BlendMode::from_u32(self.raw)
+    }
+}
+
+/// The type that allows you to build custom blend modes.
+///
+/// The resulting colors are computed as
+///
+/// ```text
+/// dstRGB = dstRGB * dst_color_factor color_operation srcRGB * src_color_factor
+/// dstA = dstA * dst_alpha_factor alpha_operation srcA * src_alpha_factor
+/// ```
+///
+/// By default, the source replaces the destination, like `BlendMode::None`.
+///
+/// # Example
+/// ```
+/// use sdl2::render::{BlendFactor, BlendOperation, CustomBlendMode};
+///
+/// // Compositing of textures with premultiplied alpha
+/// let premultiplied = CustomBlendMode::builder()
+///     .color(BlendFactor::One, BlendFactor::OneMinusSrcAlpha, BlendOperation::Add)
+///     .alpha(BlendFactor::One, BlendFactor::OneMinusSrcAlpha, BlendOperation::Add)
+///     .build();
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CustomBlendModeBuilder {
+    src_color_factor: BlendFactor,
+    dst_color_factor: BlendFactor,
+    color_operation: BlendOperation,
+    src_alpha_factor: BlendFactor,
+    dst_alpha_factor: BlendFactor,
+    alpha_operation: BlendOperation,
+}
+
+impl Default for CustomBlendModeBuilder {
+    fn default() -> CustomBlendModeBuilder {
+        CustomBlendModeBuilder {
+            src_color_factor: BlendFactor::One,
+            dst_color_factor: BlendFactor::Zero,
+            color_operation: BlendOperation::Add,
+            src_alpha_factor: BlendFactor::One,
+            dst_alpha_factor: BlendFactor::Zero,
+            alpha_operation: BlendOperation::Add,
+        }
+    }
+}
+
+impl CustomBlendModeBuilder {
+    /// Initializes a new `CustomBlendModeBuilder`.
+    pub fn new() -> CustomBlendModeBuilder {
+        CustomBlendModeBuilder::default()
+    }
+
+    /// Sets how the color components are combined.
+    pub fn color(&mut self, src_factor: BlendFactor, dst_factor: BlendFactor, operation: BlendOperation) -> &mut CustomBlendModeBuilder {
+        self.src_color_factor = src_factor;
+        self.dst_color_factor = dst_factor;
+        self.color_operation = operation;
+        self
+    }
+
+    /// Sets how the alpha components are combined.
+    pub fn alpha(&mut self, src_factor: BlendFactor, dst_factor: BlendFactor, operation: BlendOperation) -> &mut CustomBlendModeBuilder {
+        self.src_alpha_factor = src_factor;
+        self.dst_alpha_factor = dst_factor;
+        self.alpha_operation = operation;
+        self
+    }
+
+    pub fn build(&self) -> CustomBlendMode {
+        let raw = unsafe {
+            blend_ffi::SDL_ComposeCustomBlendMode(
+                self.src_color_factor.to_ll(),
+                self.dst_color_factor.to_ll(),
+                self.color_operation.to_ll(),
+                self.src_alpha_factor.to_ll(),
+                self.dst_alpha_factor.to_ll(),
+                self.alpha_operation.to_ll(),
+            )
+        };
+        CustomBlendMode { raw }
+    }
+}
+
+/// The generated bindings type blend modes as `SDL_BlendMode`, a Rust enum which cannot hold
+/// the values returned by `SDL_ComposeCustomBlendMode`. These declarations of the same functions
+/// pass blend modes as their underlying `u32` instead.
+#[allow(clashing_extern_declarations)]
+pub(crate) mod blend_ffi {
+    use libc::c_int;
+    use crate::sys;
+
+    extern "C" {
+        pub fn SDL_ComposeCustomBlendMode(
+            src_color_factor: sys::SDL_BlendFactor,
+            dst_color_factor: sys::SDL_BlendFactor,
+            color_operation: sys::SDL_BlendOperation,
+            src_alpha_factor: sys::SDL_BlendFactor,
+            dst_alpha_factor: sys::SDL_BlendFactor,
+            alpha_operation: sys::SDL_BlendOperation,
+        ) -> u32;
+        pub fn SDL_SetRenderDrawBlendMode(renderer: *mut sys::SDL_Renderer, blend_mode: u32) -> c_int;
+        pub fn SDL_GetRenderDrawBlendMode(renderer: *mut sys::SDL_Renderer, blend_mode: *mut u32) -> c_int;
+        pub fn SDL_SetTextureBlendMode(texture: *mut sys::SDL_Texture, blend_mode: u32) -> c_int;
+        pub fn SDL_GetTextureBlendMode(texture: *mut sys::SDL_Texture, blend_mode: *mut u32) -> c_int;
+        pub fn SDL_SetSurfaceBlendMode(surface: *mut sys::SDL_Surface, blend_mode: u32) -> c_int;
+        pub fn SDL_GetSurfaceBlendMode(surface: *mut sys::SDL_Surface, blend_mode: *mut u32) -> c_int;
+    }
+}
+
+#[test]
+fn blend_mode_round_trip() {
+    let modes = [BlendMode::None, BlendMode::Blend, BlendMode::Add, BlendMode::Mod, BlendMode::Invalid];
+    for &mode in &modes {
+        assert_eq!(BlendMode::from_i32(mode as i32), Some(mode));
+        assert_eq!(CustomBlendMode::from(mode).to_blend_mode(), Some(mode));
+    }
+    let custom = CustomBlendMode { raw: 0x0506_5015 };
+    assert_eq!(BlendMode::from_u32(custom.raw()), None);
+    assert_eq!(custom.to_blend_mode(), None);
+}
+
 impl RendererInfo {
     pub unsafe fn from_ll(info: &sys::SDL_RendererInfo) -> RendererInfo {
         let texture_formats: Vec<PixelFormatEnum> =
@@ -432,6 +683,370 @@ impl Canvas<Window> {
     }
 }
 
+/// Computes an integer-scaled, centered viewport for pixel-art rendering.
+///
+/// The scene is rendered at its logical resolution (e.g. into a target `Texture`), then copied
+/// to `viewport()`, the largest area of the output that is an integer multiple of the logical
+/// size. `letterbox_rects()` gives the remaining bars around it.
+///
+/// Mouse coordinates are given by SDL in window coordinates, which differ from output pixels on
+/// high-DPI displays; `window_to_logical` and `mouse_motion_to_logical` take both into account.
+///
+/// Note that when `Canvas::set_logical_size` is used, SDL already converts the mouse events to
+/// logical coordinates: use `Canvas::set_integer_scale` instead of this helper in that case.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PixelPerfectViewport {
+    logical_size: (u32, u32),
+    output_size: (u32, u32),
+    window_size: (u32, u32),
+}
+
+impl PixelPerfectViewport {
+    /// Creates a viewport for a logical resolution and an output size in pixels, which is
+    /// also assumed to be the window size.
+    pub fn new(logical_size: (u32, u32), output_size: (u32, u32)) -> PixelPerfectViewport {
+        PixelPerfectViewport { logical_size, output_size, window_size: output_size }
+    }
+
+    /// Creates a viewport for a logical resolution, using the output size and window size of a
+    /// `WindowCanvas`.
+    pub fn for_canvas(logical_size: (u32, u32), canvas: &WindowCanvas) -> Result<PixelPerfectViewport, Error> {
+        Ok(PixelPerfectViewport {
+            logical_size,
+            output_size: canvas.output_size()?,
+            window_size: canvas.window().size(),
+        })
+    }
+
+    /// Sets the window size, if it differs from the output size (high-DPI displays).
+    pub fn with_window_size(mut self, window_size: (u32, u32)) -> PixelPerfectViewport {
+        self.window_size = window_size;
+        self
+    }
+
+    pub fn logical_size(&self) -> (u32, u32) { self.logical_size }
+
+    pub fn output_size(&self) -> (u32, u32) { self.output_size }
+
+    pub fn window_size(&self) -> (u32, u32) { self.window_size }
+
+    /// The largest integer scale at which the logical area fits in the output, at least 1.
+    pub fn scale(&self) -> u32 {
+        let (lw, lh) = self.logical_size;
+        let (ow, oh) = self.output_size;
+        if lw == 0 || lh == 0 {
+            return 1;
+        }
+        (ow / lw).min(oh / lh).max(1)
+    }
+
+    /// The area of the output, in pixels, the logical area is scaled to.
+    ///
+    /// It is centered, and may exceed the output if the output is smaller than the logical size.
+    pub fn viewport(&self) -> Rect {
+        let scale = self.scale();
+        let (lw, lh) = self.logical_size;
+        let (ow, oh) = self.output_size;
+        let (w, h) = (lw * scale, lh * scale);
+        let x = (i64::from(ow) - i64::from(w)) / 2;
+        let y = (i64::from(oh) - i64::from(h)) / 2;
+        Rect::new(x as i32, y as i32, w, h)
+    }
+
+    /// The bars of the output around `viewport()`: left, right, top, bottom, omitting empty ones.
+    pub fn letterbox_rects(&self) -> Vec<Rect> {
+        let viewport = self.viewport();
+        let (ow, oh) = self.output_size;
+        let (ow, oh) = (ow as i32, oh as i32);
+        let mut rects = Vec::with_capacity(4);
+        let top = viewport.top().max(0);
+        let bottom = viewport.bottom().min(oh);
+        if top < bottom {
+            if viewport.left() > 0 {
+                rects.push(Rect::new(0, top, viewport.left() as u32, (bottom - top) as u32));
+            }
+            if viewport.right() < ow {
+                rects.push(Rect::new(viewport.right(), top, (ow - viewport.right()) as u32, (bottom - top) as u32));
+            }
+        }
+        if top > 0 {
+            rects.push(Rect::new(0, 0, ow as u32, top as u32));
+        }
+        if bottom < oh {
+            rects.push(Rect::new(0, bottom, ow as u32, (oh - bottom) as u32));
+        }
+        rects
+    }
+
+    fn window_to_output(&self, x: i32, y: i32) -> (i64, i64) {
+        let (ow, oh) = self.output_size;
+        let (ww, wh) = self.window_size;
+        if ww == 0 || wh == 0 || (ww, wh) == (ow, oh) {
+            return (i64::from(x), i64::from(y));
+        }
+        (
+            i64::from(x) * i64::from(ow) / i64::from(ww),
+            i64::from(y) * i64::from(oh) / i64::from(wh),
+        )
+    }
+
+    /// Converts a point in window coordinates to logical coordinates.
+    ///
+    /// The result lies outside of the logical area if the point is in the letterbox.
+    pub fn window_to_logical<P: Into<Point>>(&self, point: P) -> Point {
+        let point = point.into();
+        let viewport = self.viewport();
+        let scale = i64::from(self.scale());
+        let (x, y) = self.window_to_output(point.x(), point.y());
+        Point::new(
+            (x - i64::from(viewport.x())).div_euclid(scale) as i32,
+            (y - i64::from(viewport.y())).div_euclid(scale) as i32,
+        )
+    }
+
+    /// Returns whether a point in window coordinates is inside the logical area.
+    pub fn contains_window_point<P: Into<Point>>(&self, point: P) -> bool {
+        let point = self.window_to_logical(point);
+        let (lw, lh) = self.logical_size;
+        point.x() >= 0 && point.y() >= 0 && (point.x() as u32) < lw && (point.y() as u32) < lh
+    }
+
+    /// Converts the coordinates of a `MouseMotion` event to logical coordinates.
+    ///
+    /// The relative motion is divided by the scale, so small motions may be rounded to 0.
+    /// Returns `None` for other events.
+    pub fn mouse_motion_to_logical(&self, event: &Event) -> Option<Event> {
+        match *event {
+            Event::MouseMotion { timestamp, window_id, which, mousestate, x, y, xrel, yrel } => {
+                let position = self.window_to_logical((x, y));
+                let (xrel, yrel) = self.window_to_output(xrel, yrel);
+                let scale = i64::from(self.scale());
+                Some(Event::MouseMotion {
+                    timestamp,
+                    window_id,
+                    which,
+                    mousestate,
+                    x: position.x(),
+                    y: position.y(),
+                    xrel: (xrel / scale) as i32,
+                    yrel: (yrel / scale) as i32,
+                })
+            },
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn pixel_perfect_viewport_letterbox() {
+    let viewport = PixelPerfectViewport::new((320, 180), (1366, 768));
+    assert_eq!(viewport.scale(), 4);
+    assert_eq!(viewport.viewport(), Rect::new(43, 24, 1280, 720));
+    assert_eq!(viewport.letterbox_rects(), vec![
+        Rect::new(0, 24, 43, 720),
+        Rect::new(1323, 24, 43, 720),
+        Rect::new(0, 0, 1366, 24),
+        Rect::new(0, 744, 1366, 24),
+    ]);
+
+    let exact = PixelPerfectViewport::new((320, 180), (640, 360));
+    assert_eq!(exact.viewport(), Rect::new(0, 0, 640, 360));
+    assert!(exact.letterbox_rects().is_empty());
+
+    let too_small = PixelPerfectViewport::new((320, 180), (100, 100));
+    assert_eq!(too_small.scale(), 1);
+}
+
+#[test]
+fn pixel_perfect_viewport_mouse() {
+    let viewport = PixelPerfectViewport::new((320, 180), (1366, 768));
+    assert_eq!(viewport.window_to_logical((43, 24)), Point::new(0, 0));
+    assert_eq!(viewport.window_to_logical((1322, 743)), Point::new(319, 179));
+    assert_eq!(viewport.window_to_logical((0, 0)), Point::new(-11, -6));
+    assert!(!viewport.contains_window_point((42, 100)));
+    assert!(viewport.contains_window_point((43, 100)));
+
+    // high-DPI: window coordinates are half the output pixels
+    let hidpi = viewport.with_window_size((683, 384));
+    assert_eq!(hidpi.window_to_logical((22, 12)), Point::new(0, 0));
+
+    let motion = Event::MouseMotion {
+        timestamp: 0, window_id: 1, which: 0,
+        mousestate: crate::mouse::MouseState::from_sdl_state(0),
+        x: 83, y: 64, xrel: 8, yrel: -4,
+    };
+    match viewport.mouse_motion_to_logical(&motion) {
+        Some(Event::MouseMotion { x, y, xrel, yrel, .. }) => {
+            assert_eq!((x, y, xrel, yrel), (10, 10, 2, -1));
+        },
+        _ => panic!("expected a MouseMotion event"),
+    }
+    assert!(viewport.mouse_motion_to_logical(&Event::Quit { timestamp: 0 }).is_none());
+}
+
+/// The size of the borders of a nine-slice image, in source texture pixels.
+///
+/// The corners are copied unscaled, the edges are stretched along one axis and the center is
+/// stretched along both. See `Canvas::copy_nine_slice`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct NineSliceInsets {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+impl NineSliceInsets {
+    pub fn new(left: u32, top: u32, right: u32, bottom: u32) -> NineSliceInsets {
+        NineSliceInsets { left, top, right, bottom }
+    }
+
+    /// Returns insets that are the same size on every side.
+    pub fn uniform(size: u32) -> NineSliceInsets {
+        NineSliceInsets::new(size, size, size, size)
+    }
+}
+
+/// Rounds points to the nearest pixel, for the `f` drawing methods of `Canvas`.
+fn round_points(points: &[FPoint]) -> Vec<Point> {
+    points.iter().map(|point| point.to_point(RoundingMode::Nearest)).collect()
+}
+
+/// Rounds the edges of rectangles to the nearest pixel, skipping the empty ones.
+fn round_rects(rects: &[FRect]) -> Vec<Rect> {
+    rects.iter().filter_map(|rect| rect.to_rect(RoundingMode::Nearest)).collect()
+}
+
+/// Splits a span into its start, middle and end parts.
+///
+/// The start and end sizes are shrunk proportionally if they do not fit in `len`.
+fn nine_slice_spans(start: i32, len: u32, first: u32, last: u32) -> [(i32, u32); 3] {
+    let (first, last) = if first + last > len {
+        let first = (u64::from(first) * u64::from(len) / u64::from(first + last)) as u32;
+        (first, len - first)
+    } else {
+        (first, last)
+    };
+    [
+        (start, first),
+        (start + first as i32, len - first - last),
+        (start + (len - last) as i32, last),
+    ]
+}
+
+/// Computes the `(src, dst)` rectangles to copy for a nine-slice image, skipping the empty ones.
+fn nine_slice_pieces(src: Rect, insets: NineSliceInsets, dst: Rect)
+                     -> Result<Vec<(Rect, Rect)>, Error> {
+    if insets.left + insets.right > src.width() || insets.top + insets.bottom > src.height() {
+        return Err(Error::SdlError(format!(
+            "Nine-slice insets {:?} do not fit in the {}x{} source rectangle",
+            insets, src.width(), src.height())));
+    }
+
+    let src_columns = nine_slice_spans(src.x(), src.width(), insets.left, insets.right);
+    let src_rows = nine_slice_spans(src.y(), src.height(), insets.top, insets.bottom);
+    let dst_columns = nine_slice_spans(dst.x(), dst.width(), insets.left, insets.right);
+    let dst_rows = nine_slice_spans(dst.y(), dst.height(), insets.top, insets.bottom);
+
+    let mut pieces = Vec::with_capacity(9);
+    for (&(sy, sh), &(dy, dh)) in src_rows.iter().zip(dst_rows.iter()) {
+        for (&(sx, sw), &(dx, dw)) in src_columns.iter().zip(dst_columns.iter()) {
+            if sw > 0 && sh > 0 && dw > 0 && dh > 0 {
+                pieces.push((Rect::new(sx, sy, sw, sh), Rect::new(dx, dy, dw, dh)));
+            }
+        }
+    }
+    Ok(pieces)
+}
+
+/// Computes the `(src, dst)` rectangles to copy to cover `area` with `src` tiles, where one of
+/// the tiles has its top-left corner at `anchor`.
+///
+/// The tiles on the edges of `area` are cut by adjusting their source rectangle.
+fn tile_pieces(src: Rect, anchor: Point, area: Rect) -> Vec<(Rect, Rect)> {
+    let (tile_w, tile_h) = (src.width() as i32, src.height() as i32);
+    let first_x = anchor.x() + (area.x() - anchor.x()).div_euclid(tile_w) * tile_w;
+    let first_y = anchor.y() + (area.y() - anchor.y()).div_euclid(tile_h) * tile_h;
+
+    let mut pieces = Vec::new();
+    let mut tile_y = first_y;
+    while tile_y < area.bottom() {
+        let top = tile_y.max(area.y());
+        let bottom = (tile_y + tile_h).min(area.bottom());
+        let mut tile_x = first_x;
+        while tile_x < area.right() {
+            let left = tile_x.max(area.x());
+            let right = (tile_x + tile_w).min(area.right());
+            let (w, h) = ((right - left) as u32, (bottom - top) as u32);
+            pieces.push((Rect::new(src.x() + left - tile_x, src.y() + top - tile_y, w, h),
+                         Rect::new(left, top, w, h)));
+            tile_x += tile_w;
+        }
+        tile_y += tile_h;
+    }
+    pieces
+}
+
+#[test]
+fn nine_slice_layout() {
+    let insets = NineSliceInsets::new(4, 2, 4, 6);
+    let pieces = nine_slice_pieces(Rect::new(10, 10, 16, 16), insets, Rect::new(0, 0, 100, 50))
+        .unwrap();
+    assert_eq!(pieces.len(), 9);
+    assert_eq!(pieces[0], (Rect::new(10, 10, 4, 2), Rect::new(0, 0, 4, 2)));
+    assert_eq!(pieces[4], (Rect::new(14, 12, 8, 8), Rect::new(4, 2, 92, 42)));
+    assert_eq!(pieces[8], (Rect::new(22, 20, 4, 6), Rect::new(96, 44, 4, 6)));
+
+    // the center is skipped when the destination is only as large as the borders
+    let pieces = nine_slice_pieces(Rect::new(0, 0, 16, 16), NineSliceInsets::uniform(4),
+                                   Rect::new(0, 0, 8, 8)).unwrap();
+    assert_eq!(pieces.len(), 4);
+
+    // the borders shrink when the destination is too small for them
+    let pieces = nine_slice_pieces(Rect::new(0, 0, 16, 16), NineSliceInsets::new(6, 4, 2, 4),
+                                   Rect::new(0, 0, 4, 4)).unwrap();
+    assert_eq!(pieces[0], (Rect::new(0, 0, 6, 4), Rect::new(0, 0, 3, 2)));
+    assert_eq!(pieces[1], (Rect::new(14, 0, 2, 4), Rect::new(3, 0, 1, 2)));
+
+    assert!(nine_slice_pieces(Rect::new(0, 0, 8, 8), NineSliceInsets::uniform(5),
+                              Rect::new(0, 0, 32, 32)).is_err());
+}
+
+#[test]
+fn tile_layout() {
+    let src = Rect::new(8, 8, 10, 10);
+    let pieces = tile_pieces(src, Point::new(0, 0), Rect::new(0, 0, 25, 10));
+    assert_eq!(pieces, vec![
+        (Rect::new(8, 8, 10, 10), Rect::new(0, 0, 10, 10)),
+        (Rect::new(8, 8, 10, 10), Rect::new(10, 0, 10, 10)),
+        (Rect::new(8, 8, 5, 10), Rect::new(20, 0, 5, 10)),
+    ]);
+
+    // a scrolled anchor cuts the first tile on the left
+    let pieces = tile_pieces(src, Point::new(-3, 4), Rect::new(0, 4, 10, 10));
+    assert_eq!(pieces, vec![
+        (Rect::new(11, 8, 7, 10), Rect::new(0, 4, 7, 10)),
+        (Rect::new(8, 8, 3, 10), Rect::new(7, 4, 3, 10)),
+    ]);
+
+    // only the tiles inside the area are produced
+    let pieces = tile_pieces(src, Point::new(0, 0), Rect::new(1000, 995, 10, 10));
+    assert_eq!(pieces.len(), 2);
+    assert_eq!(pieces[0], (Rect::new(8, 13, 10, 5), Rect::new(1000, 995, 10, 5)));
+}
+
+#[cfg(feature = "image")]
+fn save_png(surface: &Surface, path: &Path) -> Result<(), Error> {
+    use crate::image::SaveSurface;
+    surface.save(path)
+}
+
+#[cfg(not(feature = "image"))]
+fn save_png(_surface: &Surface, path: &Path) -> Result<(), Error> {
+    Err(Error::SdlError(format!(
+        "Cannot save {}: saving PNG files requires the `image` feature", path.display())))
+}
+
 impl<T: RenderTarget> Canvas<T> {
     /// Determine whether a window supports the use of render targets.
     pub fn render_target_supported(&self) -> bool {
@@ -900,6 +1515,42 @@ impl<T> TextureCreator<T> {
     }
 }
 
+impl<T: 'static> TextureCreator<T> {
+    /// Creates a `SharedTexture`, which keeps the renderer alive; see `create_texture`.
+    pub fn create_shared_texture<F>(&self,
+                                    format: F,
+                                    access: TextureAccess,
+                                    width: u32,
+                                    height: u32)
+                                    -> Result<SharedTexture, TextureValueError>
+        where F: Into<Option<PixelFormatEnum>>
+    {
+        use self::TextureValueError::*;
+        let format: PixelFormatEnum = format.into().unwrap_or(self.default_pixel_format);
+        let result = ll_create_texture(self.context.raw(), format, access, width, height)?;
+        if result.is_null() {
+            Err(SdlError(get_error()))
+        } else {
+            unsafe { Ok(SharedTexture::from_ll(result, self.context.clone())) }
+        }
+    }
+
+    /// Creates a `SharedTexture` from an existing surface; see `create_texture_from_surface`.
+    pub fn create_shared_texture_from_surface<S: AsRef<SurfaceRef>>
+        (&self,
+         surface: S)
+         -> Result<SharedTexture, TextureValueError> {
+        use self::TextureValueError::*;
+        let result =
+            unsafe { sys::SDL_CreateTextureFromSurface(self.context.raw, surface.as_ref().raw()) };
+        if result.is_null() {
+            Err(SdlError(get_error()))
+        } else {
+            unsafe { Ok(SharedTexture::from_ll(result, self.context.clone())) }
+        }
+    }
+}
+
 /// Drawing methods
 impl<T: RenderTarget> Canvas<T> {
     pub fn raw(&self) -> *mut sys::SDL_Renderer {
@@ -930,27 +1581,44 @@ impl<T: RenderTarget> Canvas<T> {
     }
 
     /// Sets the blend mode used for drawing operations (Fill and Line).
-    pub fn set_blend_mode(&mut self, blend: BlendMode) {
+    ///
+    /// Panics if the renderer does not support the blend mode, which can only happen with a
+    /// `CustomBlendMode`; use `try_set_blend_mode` to handle this case.
+    pub fn set_blend_mode<B: Into<CustomBlendMode>>(&mut self, blend: B) {
+        if let Err(e) = self.try_set_blend_mode(blend) {
+            panic!("{}", e)
+        }
+    }
+
+    /// Sets the blend mode used for drawing operations (Fill and Line), failing if the renderer
+    /// does not support it.
+    pub fn try_set_blend_mode<B: Into<CustomBlendMode>>(&mut self, blend: B) -> Result<(), Error> {
         let ret = unsafe {
-            sys::SDL_SetRenderDrawBlendMode(self.context.raw, transmute(blend as u32))
+            blend_ffi::SDL_SetRenderDrawBlendMode(self.context.raw, blend.into().raw())
         };
-        // Should only fail on an invalid renderer
         if ret != 0 {
-            panic!(get_error())
+            Err(get_error_as_error())
+        } else {
+            Ok(())
         }
     }
 
     /// Gets the blend mode used for drawing operations.
+    ///
+    /// Returns `BlendMode::Invalid` for a custom blend mode; use `custom_blend_mode` to get it.
     pub fn blend_mode(&self) -> BlendMode {
-        let mut blend: MaybeUninit<SDL_BlendMode> = mem::MaybeUninit::uninit();
-        let ret = unsafe { sys::SDL_GetRenderDrawBlendMode(self.context.raw, blend.as_mut_ptr()) };
+        self.custom_blend_mode().to_blend_mode().unwrap_or(BlendMode::Invalid)
+    }
+
+    /// Gets the blend mode used for drawing operations, including custom blend modes.
+    pub fn custom_blend_mode(&self) -> CustomBlendMode {
+        let mut blend = 0;
+        let ret = unsafe { blend_ffi::SDL_GetRenderDrawBlendMode(self.context.raw, &mut blend) };
         // Should only fail on an invalid renderer
         if ret != 0 {
             panic!(get_error())
         } else {
-            let blend = unsafe { blend.assume_init() };
-
-            FromPrimitive::from_i64(blend as i64).unwrap()
+            CustomBlendMode { raw: blend }
         }
     }
 
@@ -1075,6 +1743,26 @@ impl<T: RenderTarget> Canvas<T> {
         (scale_x, scale_y)
     }
 
+    /// Sets whether to force integer scales for resolution-independent rendering.
+    ///
+    /// When enabled, the scale used by `set_logical_size` is restricted to integer values, which
+    /// keeps pixel art sharp at the cost of larger letterboxing.
+    pub fn set_integer_scale(&mut self, enabled: bool) -> Result<(), Error> {
+        let enabled = if enabled { sys::SDL_bool::SDL_TRUE } else { sys::SDL_bool::SDL_FALSE };
+        let ret = unsafe { sys::SDL_RenderSetIntegerScale(self.context.raw, enabled) };
+        if ret != 0 { Err(get_error_as_error()) } else { Ok(()) }
+    }
+
+    /// Gets whether integer scales are forced for resolution-independent rendering.
+    pub fn integer_scale(&self) -> bool {
+        unsafe { sys::SDL_RenderGetIntegerScale(self.context.raw) == sys::SDL_bool::SDL_TRUE }
+    }
+
+    /// Returns whether clipping is enabled on the current target.
+    pub fn is_clip_enabled(&self) -> bool {
+        unsafe { sys::SDL_RenderIsClipEnabled(self.context.raw) == sys::SDL_bool::SDL_TRUE }
+    }
+
     /// Draws a point on the current rendering target.
     /// Errors if drawing fails for any reason (e.g. driver failure)
     pub fn draw_point<P: Into<Point>>(&mut self, point: P) -> Result<(), Error> {
@@ -1198,6 +1886,90 @@ impl<T: RenderTarget> Canvas<T> {
         }
     }
 
+    /// Draws a point with floating point coordinates, rounded to the nearest pixel.
+    ///
+    /// Like the other `f` drawing methods, this converts the coordinates to integers, which is
+    /// what the SDL renderer uses.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn draw_fpoint<P: Into<FPoint>>(&mut self, point: P) -> Result<(), Error> {
+        self.draw_point(point.into().to_point(RoundingMode::Nearest))
+    }
+
+    /// Draws multiple points with floating point coordinates, rounded to the nearest pixel.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn draw_fpoints(&mut self, points: &[FPoint]) -> Result<(), Error> {
+        let points = round_points(points);
+        self.draw_points(&points[..])
+    }
+
+    /// Draws a line between points with floating point coordinates, rounded to the nearest
+    /// pixel.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn draw_fline<P1: Into<FPoint>, P2: Into<FPoint>>(&mut self,
+                                                         start: P1,
+                                                         end: P2)
+                                                         -> Result<(), Error> {
+        self.draw_line(start.into().to_point(RoundingMode::Nearest),
+                       end.into().to_point(RoundingMode::Nearest))
+    }
+
+    /// Draws a series of connected lines between points with floating point coordinates,
+    /// rounded to the nearest pixel.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn draw_flines(&mut self, points: &[FPoint]) -> Result<(), Error> {
+        let points = round_points(points);
+        self.draw_lines(&points[..])
+    }
+
+    /// Draws a rectangle with floating point coordinates, with its edges rounded to the nearest
+    /// pixel. Nothing is drawn if the rounded rectangle is empty.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn draw_frect(&mut self, rect: FRect) -> Result<(), Error> {
+        match rect.to_rect(RoundingMode::Nearest) {
+            Some(rect) => self.draw_rect(rect),
+            None => Ok(()),
+        }
+    }
+
+    /// Draws some number of rectangles with floating point coordinates, with their edges
+    /// rounded to the nearest pixel.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn draw_frects(&mut self, rects: &[FRect]) -> Result<(), Error> {
+        let rects = round_rects(rects);
+        self.draw_rects(&rects)
+    }
+
+    /// Fills a rectangle with floating point coordinates with the drawing color, with its edges
+    /// rounded to the nearest pixel. Nothing is drawn if the rounded rectangle is empty.
+    /// Passing None will fill the entire rendering target.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn fill_frect<R: Into<Option<FRect>>>(&mut self, rect: R) -> Result<(), Error> {
+        match rect.into() {
+            Some(rect) => match rect.to_rect(RoundingMode::Nearest) {
+                Some(rect) => self.fill_rect(rect),
+                None => Ok(()),
+            },
+            None => self.fill_rect(None),
+        }
+    }
+
+    /// Fills some number of rectangles with floating point coordinates with the drawing color,
+    /// with their edges rounded to the nearest pixel.
+    /// Errors if drawing fails for any reason (e.g. driver failure)
+    pub fn fill_frects(&mut self, rects: &[FRect]) -> Result<(), Error> {
+        let rects = round_rects(rects);
+        self.fill_rects(&rects)
+    }
+
+    fn copy_source_raw<X: CopySource + ?Sized>(&self, texture: &X) -> Result<*mut sys::SDL_Texture, Error> {
+        match texture.renderer_raw() {
+            Some(renderer) if renderer != self.context.raw => {
+                Err(Error::SdlError("Texture was not created with this renderer".to_owned()))
+            },
+            _ => Ok(texture.texture_raw()),
+        }
+    }
+
     /// Copies a portion of the texture to the current rendering target.
     ///
     /// * If `src` is `None`, the entire texture is copied.
@@ -1206,13 +1978,15 @@ impl<T: RenderTarget> Canvas<T> {
     ///
     /// Errors if drawing fails for any reason (e.g. driver failure),
     /// or if the provided texture does not belong to the renderer.
-    pub fn copy<R1, R2>(&mut self, texture: &Texture, src: R1, dst: R2) -> Result<(), Error>
-        where R1: Into<Option<Rect>>,
+    pub fn copy<X, R1, R2>(&mut self, texture: &X, src: R1, dst: R2) -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R1: Into<Option<Rect>>,
               R2: Into<Option<Rect>>
     {
+        let texture_raw = self.copy_source_raw(texture)?;
         let ret = unsafe {
             sys::SDL_RenderCopy(self.context.raw,
-                               texture.raw,
+                               texture_raw,
                                match src.into() {
                                    Some(ref rect) => rect.raw(),
                                    None => ptr::null(),
@@ -1239,19 +2013,21 @@ impl<T: RenderTarget> Canvas<T> {
     /// Errors if drawing fails for any reason (e.g. driver failure),
     /// if the provided texture does not belong to the renderer,
     /// or if the driver does not support RenderCopyEx.
-    pub fn copy_ex<R1, R2, P>(&mut self,
-                              texture: &Texture,
-                              src: R1,
-                              dst: R2,
-                              angle: f64,
-                              center: P,
-                              flip_horizontal: bool,
-                              flip_vertical: bool)
-                              -> Result<(), Error>
-        where R1: Into<Option<Rect>>,
+    pub fn copy_ex<X, R1, R2, P>(&mut self,
+                                 texture: &X,
+                                 src: R1,
+                                 dst: R2,
+                                 angle: f64,
+                                 center: P,
+                                 flip_horizontal: bool,
+                                 flip_vertical: bool)
+                                 -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R1: Into<Option<Rect>>,
               R2: Into<Option<Rect>>,
               P: Into<Option<Point>>
     {
+        let texture_raw = self.copy_source_raw(texture)?;
         use crate::sys::SDL_RendererFlip::*;
         let flip = unsafe { match (flip_horizontal, flip_vertical) {
             (false, false) => SDL_FLIP_NONE,
@@ -1265,7 +2041,7 @@ impl<T: RenderTarget> Canvas<T> {
 
         let ret = unsafe {
             sys::SDL_RenderCopyEx(self.context.raw,
-                                 texture.raw,
+                                 texture_raw,
                                  match src.into() {
                                      Some(ref rect) => rect.raw(),
                                      None => ptr::null(),
@@ -1285,6 +2061,137 @@ impl<T: RenderTarget> Canvas<T> {
         if ret != 0 { Err(get_error_as_error()) } else { Ok(()) }
     }
 
+    /// Like `copy`, with a destination rectangle with floating point coordinates, whose edges
+    /// are rounded to the nearest pixel. Nothing is drawn if the rounded rectangle is empty.
+    pub fn copy_f<X, R1, R2>(&mut self, texture: &X, src: R1, dst: R2) -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R1: Into<Option<Rect>>,
+              R2: Into<Option<FRect>>
+    {
+        match dst.into() {
+            Some(dst) => match dst.to_rect(RoundingMode::Nearest) {
+                Some(dst) => self.copy(texture, src, dst),
+                None => Ok(()),
+            },
+            None => self.copy(texture, src, None),
+        }
+    }
+
+    /// Like `copy_ex`, with a destination rectangle and a rotation center with floating point
+    /// coordinates, rounded to the nearest pixel. Nothing is drawn if the rounded rectangle is
+    /// empty.
+    pub fn copy_ex_f<X, R1, R2, P>(&mut self,
+                                   texture: &X,
+                                   src: R1,
+                                   dst: R2,
+                                   angle: f64,
+                                   center: P,
+                                   flip_horizontal: bool,
+                                   flip_vertical: bool)
+                                   -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R1: Into<Option<Rect>>,
+              R2: Into<Option<FRect>>,
+              P: Into<Option<FPoint>>
+    {
+        let dst = match dst.into() {
+            Some(dst) => match dst.to_rect(RoundingMode::Nearest) {
+                Some(dst) => Some(dst),
+                None => return Ok(()),
+            },
+            None => None,
+        };
+        let center = center.into().map(|center| center.to_point(RoundingMode::Nearest));
+        self.copy_ex(texture, src, dst, angle, center, flip_horizontal, flip_vertical)
+    }
+
+    /// Copies a nine-slice image from the texture, so that it covers `dst` without distorting
+    /// its borders.
+    ///
+    /// The corners given by `insets` are copied unscaled, the edges are stretched along their
+    /// length and the center fills the rest. If `dst` is too small for the borders, they are
+    /// shrunk proportionally.
+    ///
+    /// * If `src` is `None`, the entire texture is used.
+    ///
+    /// Like `copy`, the result is limited to the current viewport and clip rectangle.
+    ///
+    /// Errors if the insets do not fit in `src`, if the provided texture does not belong to the
+    /// renderer, or if drawing fails for any reason (e.g. driver failure).
+    pub fn copy_nine_slice<X, R>(&mut self,
+                                 texture: &X,
+                                 src: R,
+                                 insets: NineSliceInsets,
+                                 dst: Rect)
+                                 -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R: Into<Option<Rect>>
+    {
+        let texture_raw = self.copy_source_raw(texture)?;
+        let src = self.copy_source_rect(texture_raw, src.into());
+        for (src, dst) in nine_slice_pieces(src, insets, dst)? {
+            self.copy_piece(texture_raw, src, dst)?;
+        }
+        Ok(())
+    }
+
+    /// Fills `dst` by repeating the `src` part of the texture, without scaling it.
+    ///
+    /// The tiles are aligned on the top-left corner of `dst` moved by `offset`, which makes
+    /// scrolling backgrounds easy. Tiles are cut at the edges of `dst`, and the ones outside of
+    /// the current viewport and clip rectangle are not drawn at all.
+    ///
+    /// * If `src` is `None`, the entire texture is used.
+    ///
+    /// Errors if the provided texture does not belong to the renderer, or if drawing fails for
+    /// any reason (e.g. driver failure).
+    pub fn copy_tiled<X, R, P>(&mut self, texture: &X, src: R, dst: Rect, offset: P)
+                               -> Result<(), Error>
+        where X: CopySource + ?Sized,
+              R: Into<Option<Rect>>,
+              P: Into<Point>
+    {
+        let texture_raw = self.copy_source_raw(texture)?;
+        let src = self.copy_source_rect(texture_raw, src.into());
+
+        // The viewport and clip rectangle are given in the scaled coordinates used for drawing;
+        // the clip rectangle is relative to the viewport, like the destination rectangles.
+        let viewport = self.viewport();
+        let mut visible = Rect::new(0, 0, viewport.width(), viewport.height());
+        if self.is_clip_enabled() {
+            match self.clip_rect().and_then(|clip| clip.intersection(visible)) {
+                Some(clip) => visible = clip,
+                None => return Ok(()),
+            }
+        }
+        let area = match dst.intersection(visible) {
+            Some(area) => area,
+            None => return Ok(()),
+        };
+
+        let offset = offset.into();
+        let anchor = dst.top_left().offset(offset.x(), offset.y());
+        for (src, dst) in tile_pieces(src, anchor, area) {
+            self.copy_piece(texture_raw, src, dst)?;
+        }
+        Ok(())
+    }
+
+    fn copy_source_rect(&self, texture_raw: *mut sys::SDL_Texture, src: Option<Rect>) -> Rect {
+        src.unwrap_or_else(|| {
+            let query = InternalTexture { raw: texture_raw }.query();
+            Rect::new(0, 0, query.width, query.height)
+        })
+    }
+
+    fn copy_piece(&mut self, texture_raw: *mut sys::SDL_Texture, src: Rect, dst: Rect)
+                  -> Result<(), Error> {
+        let ret = unsafe {
+            sys::SDL_RenderCopy(self.context.raw, texture_raw, src.raw(), dst.raw())
+        };
+        if ret != 0 { Err(get_error_as_error()) } else { Ok(()) }
+    }
+
     /// Reads pixels from the current rendering target.
     /// # Remarks
     /// WARNING: This is a very slow operation, and should not be used frequently.
@@ -1324,6 +2231,70 @@ impl<T: RenderTarget> Canvas<T> {
         }
     }
 
+    /// Reads pixels from the current rendering target into a new `Surface` of the given format.
+    ///
+    /// Unlike `read_pixels`, the returned surface keeps track of its size, pitch and format, so
+    /// it can be saved or compared directly.
+    ///
+    /// * If `rect` is `None`, the entire rendering target is read.
+    ///
+    /// # Remarks
+    /// WARNING: This is a very slow operation, and should not be used frequently.
+    pub fn read_surface<R: Into<Option<Rect>>>(&self,
+                                               rect: R,
+                                               format: pixels::PixelFormatEnum)
+                                               -> Result<Surface<'static>, Error> {
+        let rect = rect.into();
+        let (w, h) = match rect {
+            Some(ref rect) => rect.size(),
+            None => self.output_size()?,
+        };
+        let mut surface = Surface::new(w, h, format)?;
+        let pitch = surface.pitch();
+        let ret = surface.with_lock_mut(|pixels| unsafe {
+            sys::SDL_RenderReadPixels(self.context.raw,
+                                     match rect {
+                                         Some(ref rect) => rect.raw(),
+                                         None => ptr::null(),
+                                     },
+                                     format as u32,
+                                     pixels.as_mut_ptr() as *mut c_void,
+                                     pitch as c_int)
+        });
+
+        if ret == 0 { Ok(surface) } else { Err(get_error_as_error()) }
+    }
+
+    /// Saves the entire rendering target to a file.
+    ///
+    /// The format is chosen from the extension of the file name: `.bmp`, or `.png` with the
+    /// `image` feature. This works with the software renderer too, which makes it usable in
+    /// headless tests.
+    ///
+    /// Errors if reading the pixels or writing the file fails, if the extension is missing or
+    /// not one of these, or if a PNG file is requested without the `image` feature.
+    ///
+    /// # Remarks
+    /// WARNING: This is a very slow operation, and should not be used frequently.
+    pub fn screenshot<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let extension = path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        let is_png = match extension.as_deref() {
+            Some("bmp") => false,
+            Some("png") => true,
+            _ => return Err(Error::SdlError(format!(
+                "Cannot save {}: the file name must end with .bmp or .png", path.display()))),
+        };
+        let surface = self.read_surface(None, PixelFormatEnum::RGB24)?;
+        if is_png {
+            save_png(&surface, path)
+        } else {
+            surface.save_bmp(path)
+        }
+    }
+
     /// Creates a texture for a rendering context.
     ///
     /// If format is `None`, the format will be the one the parent Window or Surface uses.
@@ -1734,26 +2705,37 @@ impl InternalTexture {
         if ret != 0 { panic!(get_error()) } else { alpha }
     }
 
-    pub fn set_blend_mode(&mut self, blend: BlendMode) {
+    pub fn set_blend_mode(&mut self, blend: CustomBlendMode) {
+        if let Err(e) = self.try_set_blend_mode(blend) {
+            panic!("Error setting blend: {}", e)
+        }
+    }
+
+    pub fn try_set_blend_mode(&mut self, blend: CustomBlendMode) -> Result<(), Error> {
         let ret = unsafe {
-            sys::SDL_SetTextureBlendMode(self.raw, transmute(blend as u32))
+            blend_ffi::SDL_SetTextureBlendMode(self.raw, blend.raw())
         };
 
         if ret != 0 {
-            panic!("Error setting blend: {}", get_error())
+            Err(get_error_as_error())
+        } else {
+            Ok(())
         }
     }
 
     pub fn blend_mode(&self) -> BlendMode {
-        let mut blend: MaybeUninit<SDL_BlendMode> = mem::MaybeUninit::uninit();
-        let ret = unsafe { sys::SDL_GetTextureBlendMode(self.raw, blend.as_mut_ptr()) };
+        self.custom_blend_mode().to_blend_mode().unwrap_or(BlendMode::Invalid)
+    }
+
+    pub fn custom_blend_mode(&self) -> CustomBlendMode {
+        let mut blend = 0;
+        let ret = unsafe { blend_ffi::SDL_GetTextureBlendMode(self.raw, &mut blend) };
 
         // Should only fail on an invalid texture
         if ret != 0 {
             panic!(get_error())
         } else {
-            let blend = unsafe { blend.assume_init() };
-            FromPrimitive::from_i64(blend as i64).unwrap()
+            CustomBlendMode { raw: blend }
         }
     }
 
@@ -1815,26 +2797,11 @@ impl InternalTexture {
         }
     }
 
-    pub fn update_yuv<R>(&mut self,
-                         rect: R,
-                         y_plane: &[u8],
-                         y_pitch: usize,
-                         u_plane: &[u8],
-                         u_pitch: usize,
-                         v_plane: &[u8],
-                         v_pitch: usize)
-                         -> Result<(), UpdateTextureYUVError>
-        where R: Into<Option<Rect>>
-    {
+    /// Checks that a rectangle to update in a 4:2:0 YUV texture is aligned on chroma samples
+    /// and inside the texture, and returns its size.
+    fn check_yuv_rect(&self, rect: Option<Rect>) -> Result<(u32, u32), UpdateTextureYUVError> {
         use self::UpdateTextureYUVError::*;
 
-        let rect = rect.into();
-
-        let rect_raw_ptr = match rect {
-            Some(ref rect) => rect.raw(),
-            None => ptr::null(),
-        };
-
         if let Some(ref r) = rect {
             if r.x() % 2 != 0 {
                 return Err(XMustBeMultipleOfTwoForFormat(r.x()));
@@ -1862,12 +2829,36 @@ impl InternalTexture {
             }
         }
 
+        Ok(match rect {
+            Some(ref r) => r.size(),
+            None => (tex_info.width, tex_info.height),
+        })
+    }
+
+    pub fn update_yuv<R>(&mut self,
+                         rect: R,
+                         y_plane: &[u8],
+                         y_pitch: usize,
+                         u_plane: &[u8],
+                         u_pitch: usize,
+                         v_plane: &[u8],
+                         v_pitch: usize)
+                         -> Result<(), UpdateTextureYUVError>
+        where R: Into<Option<Rect>>
+    {
+        use self::UpdateTextureYUVError::*;
+
+        let rect = rect.into();
+
+        let rect_raw_ptr = match rect {
+            Some(ref rect) => rect.raw(),
+            None => ptr::null(),
+        };
+
         // We need the height in order to check the array slice lengths.
         // Checking the lengths can prevent buffer overruns in SDL_UpdateYUVTexture.
-        let height = match rect {
-            Some(ref r) => r.height(),
-            None => tex_info.height,
-        } as usize;
+        let (_, height) = self.check_yuv_rect(rect)?;
+        let height = height as usize;
 
         //let wrong_length =
         if y_plane.len() != (y_pitch * height) {
@@ -1940,6 +2931,70 @@ impl InternalTexture {
         }
     }
 
+    pub fn update_nv<R>(&mut self,
+                        rect: R,
+                        y_plane: &[u8],
+                        y_pitch: usize,
+                        uv_plane: &[u8],
+                        uv_pitch: usize)
+                        -> Result<(), UpdateTextureYUVError>
+        where R: Into<Option<Rect>>
+    {
+        use self::UpdateTextureYUVError::*;
+
+        let format = self.query().format;
+        if format != PixelFormatEnum::NV12 && format != PixelFormatEnum::NV21 {
+            return Err(SdlError(format!("Cannot update a {:?} texture with NV planes", format)));
+        }
+
+        let rect = rect.into();
+        let (width, height) = self.check_yuv_rect(rect)?;
+        let (width, height) = (width as usize, height as usize);
+        // The UV plane has one pair of bytes for each 2x2 block of pixels, rounded up.
+        let (uv_width, uv_height) = (2 * width.div_ceil(2), height.div_ceil(2));
+
+        for &(plane, length, pitch, width, height) in
+                &[("y", y_plane.len(), y_pitch, width, height),
+                  ("uv", uv_plane.len(), uv_pitch, uv_width, uv_height)] {
+            if pitch < width {
+                return Err(SdlError(format!(
+                    "The {} plane pitch ({}) is smaller than the width ({})", plane, pitch, width)));
+            }
+            if length != pitch * height {
+                return Err(InvalidPlaneLength { plane, length, pitch, height });
+            }
+        }
+
+        // SDL 2.0.9 has no SDL_UpdateNVTexture: SDL_UpdateTexture takes both planes in one
+        // buffer, the interleaved UV rows having a pitch of `2 * pitch.div_ceil(2)`.
+        let mut pixels = Vec::with_capacity(format.byte_size_from_pitch_and_height(width, height));
+        for row in y_plane.chunks(y_pitch) {
+            pixels.extend_from_slice(&row[..width]);
+        }
+        for row in uv_plane.chunks(uv_pitch) {
+            pixels.extend_from_slice(&row[..uv_width]);
+        }
+
+        let pitch = match validate_int(width as u32, "pitch") {
+            Ok(pitch) => pitch,
+            Err(_) => return Err(PitchOverflows { plane: "y", value: width }),
+        };
+        let result = unsafe {
+            sys::SDL_UpdateTexture(self.raw,
+                                  match rect {
+                                      Some(ref rect) => rect.raw(),
+                                      None => ptr::null(),
+                                  },
+                                  pixels.as_ptr() as *const _,
+                                  pitch)
+        };
+        if result != 0 {
+            Err(SdlError(get_error()))
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn with_lock<F, R, R2>(&mut self, rect: R2, func: F) -> Result<R, Error>
         where F: FnOnce(&mut [u8], usize) -> R,
               R2: Into<Option<Rect>>
@@ -2048,18 +3103,36 @@ impl<'r> Texture<'r> {
         InternalTexture{ raw: self.raw }.alpha_mod()
     }
 
-    /// Sets the blend mode used for drawing operations (Fill and Line).
+    /// Sets the blend mode used for texture copy operations.
+    ///
+    /// Panics if the renderer does not support the blend mode, which can only happen with a
+    /// `CustomBlendMode`; use `try_set_blend_mode` to handle this case.
+    #[inline]
+    pub fn set_blend_mode<B: Into<CustomBlendMode>>(&mut self, blend: B) {
+        InternalTexture{ raw: self.raw }.set_blend_mode(blend.into())
+    }
+
+    /// Sets the blend mode used for texture copy operations, failing if the renderer does not
+    /// support it.
     #[inline]
-    pub fn set_blend_mode(&mut self, blend: BlendMode) {
-        InternalTexture{ raw: self.raw }.set_blend_mode(blend)
+    pub fn try_set_blend_mode<B: Into<CustomBlendMode>>(&mut self, blend: B) -> Result<(), Error> {
+        InternalTexture{ raw: self.raw }.try_set_blend_mode(blend.into())
     }
 
     /// Gets the blend mode used for texture copy operations.
+    ///
+    /// Returns `BlendMode::Invalid` for a custom blend mode; use `custom_blend_mode` to get it.
     #[inline]
     pub fn blend_mode(&self) -> BlendMode {
         InternalTexture{ raw: self.raw }.blend_mode()
     }
 
+    /// Gets the blend mode used for texture copy operations, including custom blend modes.
+    #[inline]
+    pub fn custom_blend_mode(&self) -> CustomBlendMode {
+        InternalTexture{ raw: self.raw }.custom_blend_mode()
+    }
+
     /// Updates the given texture rectangle with new pixel data.
     ///
     /// `pitch` is the number of bytes in a row of pixel data, including padding
@@ -2091,6 +3164,23 @@ impl<'r> Texture<'r> {
         InternalTexture { raw: self.raw }.update_yuv(rect, y_plane, y_pitch, u_plane, u_pitch, v_plane, v_pitch)
     }
 
+    /// Updates a rectangle within a two-plane NV12 or NV21 texture with new pixel data.
+    ///
+    /// `uv_plane` holds the interleaved chroma samples, with half the height of `y_plane`.
+    /// Like `update_yuv`, the rectangle must be inside the texture, with even coordinates and
+    /// size, and the planes must be exactly `pitch * height` bytes long.
+    #[inline]
+    pub fn update_nv<R>(&mut self,
+                        rect: R,
+                        y_plane: &[u8],
+                        y_pitch: usize,
+                        uv_plane: &[u8],
+                        uv_pitch: usize)
+                        -> Result<(), UpdateTextureYUVError>
+        where R: Into<Option<Rect>> {
+        InternalTexture { raw: self.raw }.update_nv(rect, y_plane, y_pitch, uv_plane, uv_pitch)
+    }
+
     /// Locks the texture for **write-only** pixel access.
     /// The texture must have been created with streaming access.
     ///
@@ -2109,6 +3199,33 @@ impl<'r> Texture<'r> {
         InternalTexture { raw: self.raw }.with_lock(rect, func)
     }
 
+    /// Locks the texture like `with_lock`, giving typed access to its pixels.
+    ///
+    /// `P` must have the size of a pixel of the texture format, e.g. `u32` for `ARGB8888` or
+    /// `[u8; 3]` for `RGB24`; otherwise, or for YUV formats, an error is returned.
+    ///
+    /// ```rust,no_run
+    /// # use sdl2::pixels::Color;
+    /// # use sdl2::render::Texture;
+    /// # let mut texture : Texture = unimplemented!();
+    /// texture.with_lock_typed::<u32, _, _, _>(None, |lock| {
+    ///     for row in lock.rows_mut() {
+    ///         for pixel in row.iter_mut() {
+    ///             *pixel = 0xFF00_00FF;
+    ///         }
+    ///     }
+    ///     lock.put_pixel(0, 0, Color::RGB(255, 255, 255));
+    /// }).unwrap();
+    /// ```
+    #[inline]
+    pub fn with_lock_typed<P, F, R, R2>(&mut self, rect: R2, func: F) -> Result<R, Error>
+        where P: LockPixel,
+              F: FnOnce(&mut TextureLock<P>) -> R,
+              R2: Into<Option<Rect>>
+    {
+        InternalTexture { raw: self.raw }.with_lock_typed(rect, func)
+    }
+
     /// Binds an OpenGL/ES/ES2 texture to the current
     /// context for use with when rendering OpenGL primitives directly.
     #[inline]
@@ -2166,18 +3283,36 @@ impl<> Texture<> {
         InternalTexture{ raw: self.raw }.alpha_mod()
     }
 
-    /// Sets the blend mode used for drawing operations (Fill and Line).
+    /// Sets the blend mode used for texture copy operations.
+    ///
+    /// Panics if the renderer does not support the blend mode, which can only happen with a
+    /// `CustomBlendMode`; use `try_set_blend_mode` to handle this case.
     #[inline]
-    pub fn set_blend_mode(&mut self, blend: BlendMode) {
-        InternalTexture{ raw: self.raw }.set_blend_mode(blend)
+    pub fn set_blend_mode<B: Into<CustomBlendMode>>(&mut self, blend: B) {
+        InternalTexture{ raw: self.raw }.set_blend_mode(blend.into())
+    }
+
+    /// Sets the blend mode used for texture copy operations, failing if the renderer does not
+    /// support it.
+    #[inline]
+    pub fn try_set_blend_mode<B: Into<CustomBlendMode>>(&mut self, blend: B) -> Result<(), Error> {
+        InternalTexture{ raw: self.raw }.try_set_blend_mode(blend.into())
     }
 
     /// Gets the blend mode used for texture copy operations.
+    ///
+    /// Returns `BlendMode::Invalid` for a custom blend mode; use `custom_blend_mode` to get it.
     #[inline]
     pub fn blend_mode(&self) -> BlendMode {
         InternalTexture{ raw: self.raw }.blend_mode()
     }
 
+    /// Gets the blend mode used for texture copy operations, including custom blend modes.
+    #[inline]
+    pub fn custom_blend_mode(&self) -> CustomBlendMode {
+        InternalTexture{ raw: self.raw }.custom_blend_mode()
+    }
+
     /// Updates the given texture rectangle with new pixel data.
     ///
     /// `pitch` is the number of bytes in a row of pixel data, including padding
@@ -2209,6 +3344,23 @@ impl<> Texture<> {
         InternalTexture { raw: self.raw }.update_yuv(rect, y_plane, y_pitch, u_plane, u_pitch, v_plane, v_pitch)
     }
 
+    /// Updates a rectangle within a two-plane NV12 or NV21 texture with new pixel data.
+    ///
+    /// `uv_plane` holds the interleaved chroma samples, with half the height of `y_plane`.
+    /// Like `update_yuv`, the rectangle must be inside the texture, with even coordinates and
+    /// size, and the planes must be exactly `pitch * height` bytes long.
+    #[inline]
+    pub fn update_nv<R>(&mut self,
+                        rect: R,
+                        y_plane: &[u8],
+                        y_pitch: usize,
+                        uv_plane: &[u8],
+                        uv_pitch: usize)
+                        -> Result<(), UpdateTextureYUVError>
+        where R: Into<Option<Rect>> {
+        InternalTexture { raw: self.raw }.update_nv(rect, y_plane, y_pitch, uv_plane, uv_pitch)
+    }
+
     /// Locks the texture for **write-only** pixel access.
     /// The texture must have been created with streaming access.
     ///
@@ -2227,6 +3379,33 @@ impl<> Texture<> {
         InternalTexture { raw: self.raw }.with_lock(rect, func)
     }
 
+    /// Locks the texture like `with_lock`, giving typed access to its pixels.
+    ///
+    /// `P` must have the size of a pixel of the texture format, e.g. `u32` for `ARGB8888` or
+    /// `[u8; 3]` for `RGB24`; otherwise, or for YUV formats, an error is returned.
+    ///
+    /// ```rust,no_run
+    /// # use sdl2::pixels::Color;
+    /// # use sdl2::render::Texture;
+    /// # let mut texture : Texture = unimplemented!();
+    /// texture.with_lock_typed::<u32, _, _, _>(None, |lock| {
+    ///     for row in lock.rows_mut() {
+    ///         for pixel in row.iter_mut() {
+    ///             *pixel = 0xFF00_00FF;
+    ///         }
+    ///     }
+    ///     lock.put_pixel(0, 0, Color::RGB(255, 255, 255));
+    /// }).unwrap();
+    /// ```
+    #[inline]
+    pub fn with_lock_typed<P, F, R, R2>(&mut self, rect: R2, func: F) -> Result<R, Error>
+        where P: LockPixel,
+              F: FnOnce(&mut TextureLock<P>) -> R,
+              R2: Into<Option<Rect>>
+    {
+        InternalTexture { raw: self.raw }.with_lock_typed(rect, func)
+    }
+
     /// Binds an OpenGL/ES/ES2 texture to the current
     /// context for use with when rendering OpenGL primitives directly.
     #[inline]
@@ -2252,6 +3431,231 @@ impl<> Texture<> {
     }
 }
 
+/// A texture that can be drawn with `Canvas::copy` and `Canvas::copy_ex`.
+///
+/// # Safety
+/// `texture_raw` must return a valid `SDL_Texture`, which stays alive as long as the
+/// implementor. If `renderer_raw` returns `Some`, it must be the renderer of that texture.
+pub unsafe trait CopySource {
+    /// The raw `SDL_Texture` to draw.
+    fn texture_raw(&self) -> *mut sys::SDL_Texture;
+
+    /// The renderer the texture belongs to, if it is known.
+    ///
+    /// When it is, `Canvas` checks it before drawing.
+    fn renderer_raw(&self) -> Option<*mut sys::SDL_Renderer> {
+        None
+    }
+}
+
+#[cfg(not(feature = "unsafe_textures"))]
+unsafe impl<'r> CopySource for Texture<'r> {
+    fn texture_raw(&self) -> *mut sys::SDL_Texture {
+        self.raw
+    }
+}
+
+#[cfg(feature = "unsafe_textures")]
+unsafe impl CopySource for Texture {
+    fn texture_raw(&self) -> *mut sys::SDL_Texture {
+        self.raw
+    }
+}
+
+#[cfg(not(feature = "unsafe_textures"))]
+type OwnedTexture = Texture<'static>;
+
+#[cfg(feature = "unsafe_textures")]
+type OwnedTexture = Texture;
+
+struct SharedTextureInner {
+    // Declared first, so that the texture is destroyed before the renderer
+    texture: OwnedTexture,
+    _context: Rc<dyn Any>,
+    renderer: *mut sys::SDL_Renderer,
+    // Set while `with_lock` runs, so that no clone can alias the locked pixels
+    locked: Cell<bool>,
+}
+
+#[cfg(feature = "unsafe_textures")]
+impl Drop for SharedTextureInner {
+    fn drop(&mut self) {
+        unsafe {
+            sys::SDL_DestroyTexture(self.texture.raw);
+        }
+    }
+}
+
+/// A reference-counted texture, which keeps its renderer alive.
+///
+/// Unlike `Texture`, it is not bound to the lifetime of its `TextureCreator`, so it can be
+/// stored in long-lived structures such as asset caches, and cloning it is cheap. The renderer
+/// (and its `Window` or `Surface`) is only destroyed once every `SharedTexture` created from it
+/// is dropped.
+///
+/// `Canvas::copy` and `Canvas::copy_ex` return an error if a `SharedTexture` is drawn on a
+/// `Canvas` other than the one it was created for.
+///
+/// The pixels, color mod, alpha mod and blend mode are shared between clones and can be set
+/// through a shared reference. It does not dereference to a `Texture`, so that it cannot be
+/// passed to the methods taking a `Texture`, which do not check its renderer.
+///
+/// Obtain with `TextureCreator::create_shared_texture` or
+/// `TextureCreator::create_shared_texture_from_surface`.
+#[derive(Clone)]
+pub struct SharedTexture {
+    inner: Rc<SharedTextureInner>,
+}
+
+impl SharedTexture {
+    unsafe fn from_ll<T: 'static>(raw: *mut sys::SDL_Texture, context: Rc<RendererContext<T>>) -> SharedTexture {
+        let renderer = context.raw;
+        SharedTexture {
+            inner: Rc::new(SharedTextureInner {
+                texture: Texture {
+                    raw,
+                    #[cfg(not(feature = "unsafe_textures"))]
+                    _marker: PhantomData,
+                },
+                _context: context,
+                renderer,
+                locked: Cell::new(false),
+            })
+        }
+    }
+
+    fn check_unlocked(&self) -> Result<(), String> {
+        if self.inner.locked.get() {
+            Err("The texture is locked".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns true if both handles refer to the same texture.
+    pub fn ptr_eq(&self, other: &SharedTexture) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Queries the attributes of the texture.
+    pub fn query(&self) -> TextureQuery {
+        InternalTexture { raw: self.inner.texture.raw }.query()
+    }
+
+    /// Sets an additional color value multiplied into render copy operations.
+    pub fn set_color_mod(&self, red: u8, green: u8, blue: u8) {
+        InternalTexture { raw: self.inner.texture.raw }.set_color_mod(red, green, blue)
+    }
+
+    /// Gets the additional color value multiplied into render copy operations.
+    pub fn color_mod(&self) -> (u8, u8, u8) {
+        InternalTexture { raw: self.inner.texture.raw }.color_mod()
+    }
+
+    /// Sets an additional alpha value multiplied into render copy operations.
+    pub fn set_alpha_mod(&self, alpha: u8) {
+        InternalTexture { raw: self.inner.texture.raw }.set_alpha_mod(alpha)
+    }
+
+    /// Gets the additional alpha value multiplied into render copy operations.
+    pub fn alpha_mod(&self) -> u8 {
+        InternalTexture { raw: self.inner.texture.raw }.alpha_mod()
+    }
+
+    /// Sets the blend mode used for texture copy operations, failing if the renderer does not
+    /// support it.
+    pub fn set_blend_mode<B: Into<CustomBlendMode>>(&self, blend: B) -> Result<(), Error> {
+        InternalTexture { raw: self.inner.texture.raw }.try_set_blend_mode(blend.into())
+    }
+
+    /// Gets the blend mode used for texture copy operations.
+    ///
+    /// Returns `BlendMode::Invalid` for a custom blend mode; use `custom_blend_mode` to get it.
+    pub fn blend_mode(&self) -> BlendMode {
+        InternalTexture { raw: self.inner.texture.raw }.blend_mode()
+    }
+
+    /// Gets the blend mode used for texture copy operations, including custom blend modes.
+    pub fn custom_blend_mode(&self) -> CustomBlendMode {
+        InternalTexture { raw: self.inner.texture.raw }.custom_blend_mode()
+    }
+
+    /// Updates the given texture rectangle with new pixel data; see `Texture::update`.
+    ///
+    /// Fails while the texture is locked by `with_lock`.
+    pub fn update<R>(&self, rect: R, pixel_data: &[u8], pitch: usize)
+                     -> Result<(), UpdateTextureError>
+        where R: Into<Option<Rect>>
+    {
+        self.check_unlocked().map_err(UpdateTextureError::SdlError)?;
+        InternalTexture { raw: self.inner.texture.raw }.update(rect, pixel_data, pitch)
+    }
+
+    /// Updates a rectangle within a planar YV12 or IYUV texture with new pixel data; see
+    /// `Texture::update_yuv`.
+    pub fn update_yuv<R>(&self,
+                         rect: R,
+                         y_plane: &[u8],
+                         y_pitch: usize,
+                         u_plane: &[u8],
+                         u_pitch: usize,
+                         v_plane: &[u8],
+                         v_pitch: usize)
+                         -> Result<(), UpdateTextureYUVError>
+        where R: Into<Option<Rect>>
+    {
+        self.check_unlocked().map_err(UpdateTextureYUVError::SdlError)?;
+        InternalTexture { raw: self.inner.texture.raw }
+            .update_yuv(rect, y_plane, y_pitch, u_plane, u_pitch, v_plane, v_pitch)
+    }
+
+    /// Updates a rectangle within a two-plane NV12 or NV21 texture with new pixel data; see
+    /// `Texture::update_nv`.
+    pub fn update_nv<R>(&self,
+                        rect: R,
+                        y_plane: &[u8],
+                        y_pitch: usize,
+                        uv_plane: &[u8],
+                        uv_pitch: usize)
+                        -> Result<(), UpdateTextureYUVError>
+        where R: Into<Option<Rect>>
+    {
+        self.check_unlocked().map_err(UpdateTextureYUVError::SdlError)?;
+        InternalTexture { raw: self.inner.texture.raw }
+            .update_nv(rect, y_plane, y_pitch, uv_plane, uv_pitch)
+    }
+
+    /// Locks the texture for **write-only** pixel access; see `Texture::with_lock`.
+    ///
+    /// Fails if the texture is already locked, e.g. through a clone of this handle from inside
+    /// `func`.
+    pub fn with_lock<F, R, R2>(&self, rect: R2, func: F) -> Result<R, Error>
+        where F: FnOnce(&mut [u8], usize) -> R,
+              R2: Into<Option<Rect>>
+    {
+        self.check_unlocked().map_err(Error::SdlError)?;
+        self.inner.locked.set(true);
+        let result = InternalTexture { raw: self.inner.texture.raw }.with_lock(rect, func);
+        self.inner.locked.set(false);
+        result
+    }
+
+    /// Gets the raw pointer to the `SDL_Renderer` this texture belongs to.
+    pub fn renderer(&self) -> *mut sys::SDL_Renderer {
+        self.inner.renderer
+    }
+}
+
+unsafe impl CopySource for SharedTexture {
+    fn texture_raw(&self) -> *mut sys::SDL_Texture {
+        self.inner.texture.raw
+    }
+
+    fn renderer_raw(&self) -> Option<*mut sys::SDL_Renderer> {
+        Some(self.inner.renderer)
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct DriverIterator {
     length: i32,
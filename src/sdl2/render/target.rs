@@ -0,0 +1,132 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::pixels::Color;
+use crate::rect::Rect;
+use crate::sys;
+use super::{Canvas, RenderTarget, SdlError, TargetRenderError, Texture};
+
+/// The canvas state saved when pushing a render target.
+#[derive(Clone, Copy, Debug)]
+struct CanvasState {
+    target: *mut sys::SDL_Texture,
+    viewport: Rect,
+    clip_rect: Option<Rect>,
+    scale: (f32, f32),
+    draw_color: Color,
+}
+
+impl CanvasState {
+    fn save<T: RenderTarget>(canvas: &Canvas<T>) -> CanvasState {
+        CanvasState {
+            target: unsafe { canvas.get_raw_target() },
+            viewport: canvas.viewport(),
+            clip_rect: canvas.clip_rect(),
+            scale: canvas.scale(),
+            draw_color: canvas.draw_color(),
+        }
+    }
+
+    fn restore<T: RenderTarget>(&self, canvas: &mut Canvas<T>) -> Result<(), SdlError> {
+        unsafe { canvas.set_raw_target(self.target) }?;
+        // The viewport and clip rectangle are stored in scaled coordinates, so the scale must be
+        // restored first.
+        canvas.set_scale(self.scale.0, self.scale.1).map_err(|e| SdlError(e.to_string()))?;
+        canvas.set_viewport(self.viewport);
+        canvas.set_clip_rect(self.clip_rect);
+        canvas.set_draw_color(self.draw_color);
+        Ok(())
+    }
+}
+
+/// A scoped render target, created by `Canvas::push_target` or `Canvas::push_state`.
+///
+/// The guard dereferences to the `Canvas`, which draws to the pushed texture. When the guard is
+/// dropped (or `pop` is called), the previous render target is restored, along with the
+/// viewport, clip rectangle, scale and draw color it had when the guard was created.
+///
+/// Targets can be nested by pushing another texture on the guard:
+///
+/// ```rust,no_run
+/// # use sdl2::render::{Canvas, Texture};
+/// # use sdl2::video::Window;
+/// # use sdl2::pixels::Color;
+/// # let mut canvas : Canvas<Window> = unimplemented!();
+/// # let mut scene : Texture = unimplemented!();
+/// # let mut minimap : Texture = unimplemented!();
+/// {
+///     let mut scene_canvas = canvas.push_target(&mut scene).unwrap();
+///     scene_canvas.set_draw_color(Color::RGB(0, 0, 64));
+///     scene_canvas.clear();
+///     {
+///         let mut minimap_canvas = scene_canvas.push_target(&mut minimap).unwrap();
+///         minimap_canvas.clear();
+///     }
+///     // drawing to `scene` again, with its draw color restored
+///     scene_canvas.copy(&minimap, None, None).unwrap();
+/// }
+/// // drawing to the window again
+/// canvas.copy(&scene, None, None).unwrap();
+/// canvas.present();
+/// ```
+pub struct RenderTargetGuard<'g, T: RenderTarget + 'g> {
+    canvas: &'g mut Canvas<T>,
+    saved: CanvasState,
+    popped: bool,
+}
+
+impl<T: RenderTarget> Canvas<T> {
+    /// Makes `texture` the render target until the returned guard is dropped.
+    ///
+    /// Unlike `with_texture_canvas`, guards compose: pushing a texture while another one is the
+    /// render target restores that texture, not the default target, when the guard is dropped.
+    ///
+    /// The texture must be created with the texture access
+    /// `sdl2::render::TextureAccess::Target`, or SDL returns an error.
+    pub fn push_target<'g>(&'g mut self, texture: &'g mut Texture)
+                           -> Result<RenderTargetGuard<'g, T>, TargetRenderError> {
+        if !self.render_target_supported() {
+            return Err(TargetRenderError::NotSupported);
+        }
+        let saved = CanvasState::save(self);
+        unsafe { self.set_raw_target(texture.raw) }.map_err(TargetRenderError::SdlError)?;
+        Ok(RenderTargetGuard { canvas: self, saved, popped: false })
+    }
+
+    /// Saves the viewport, clip rectangle, scale and draw color until the returned guard is
+    /// dropped, without changing the render target.
+    pub fn push_state(&mut self) -> RenderTargetGuard<'_, T> {
+        let saved = CanvasState::save(self);
+        RenderTargetGuard { canvas: self, saved, popped: false }
+    }
+}
+
+impl<'g, T: RenderTarget> RenderTargetGuard<'g, T> {
+    /// Restores the previous render target and state, reporting errors which are ignored when
+    /// the guard is dropped.
+    pub fn pop(mut self) -> Result<(), TargetRenderError> {
+        self.popped = true;
+        self.saved.restore(self.canvas).map_err(TargetRenderError::SdlError)
+    }
+}
+
+impl<'g, T: RenderTarget> Deref for RenderTargetGuard<'g, T> {
+    type Target = Canvas<T>;
+
+    fn deref(&self) -> &Canvas<T> {
+        self.canvas
+    }
+}
+
+impl<'g, T: RenderTarget> DerefMut for RenderTargetGuard<'g, T> {
+    fn deref_mut(&mut self) -> &mut Canvas<T> {
+        self.canvas
+    }
+}
+
+impl<'g, T: RenderTarget> Drop for RenderTargetGuard<'g, T> {
+    fn drop(&mut self) {
+        if !self.popped {
+            let _ = self.saved.restore(self.canvas);
+        }
+    }
+}
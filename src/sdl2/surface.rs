@@ -10,9 +10,9 @@ use std::ptr;
 use libc::c_int;
 use num::FromPrimitive;
 use crate::pixels;
-use crate::render::{BlendMode, Canvas};
+use crate::pixels::Color;
+use crate::render::{BlendMode, Canvas, blend_ffi};
 use crate::rwops::RWops;
-use std::mem::transmute;
 
 use crate::sys;
 
@@ -115,8 +115,20 @@ impl<'a> Surface<'a> {
     /// let surface = Surface::new(512, 512, PixelFormatEnum::RGB24).unwrap();
     /// ```
     pub fn new(width: u32, height: u32, format: pixels::PixelFormatEnum) -> Result<Surface<'static>, Error> {
-        let masks = format.into_masks()?;
-        Surface::from_pixelmasks(width, height, masks)
+        unsafe {
+            if width >= (1<<31) || height >= (1<<31) {
+                Err(Error::SdlError("Image is too large.".to_owned()))
+            } else {
+                let raw = sys::SDL_CreateRGBSurfaceWithFormat(0, width as c_int, height as c_int,
+                    bits_per_pixel(format), format as u32);
+
+                if raw.is_null() {
+                    Err(get_error_as_error())
+                } else {
+                    Ok(Surface::from_ll(raw))
+                }
+            }
+        }
     }
 
     /// Creates a new surface using pixel masks.
@@ -147,9 +159,33 @@ impl<'a> Surface<'a> {
     }
 
     /// Creates a new surface from an existing buffer, using a pixel format.
+    ///
+    /// Errors if `pitch` is smaller than a row of `width` pixels, or if `data` is too small to
+    /// hold `height` rows of `pitch` bytes, not counting the padding after the last row.
     pub fn from_data(data: &'a mut [u8], width: u32, height: u32, pitch: u32, format: pixels::PixelFormatEnum) -> Result<Surface<'a>, Error> {
-        let masks = format.into_masks()?;
-        Surface::from_data_pixelmasks(data, width, height, pitch, masks)
+        // The sizes only overflow `usize` on 32-bit targets
+        let too_large = || Error::SdlError("Image is too large.".to_owned());
+        unsafe {
+            if width >= (1<<31) || height >= (1<<31) {
+                Err(too_large())
+            } else if pitch >= (1<<31) {
+                Err(Error::SdlError("Pitch is too large.".to_owned()))
+            } else if (pitch as usize) < row_bytes(width, format).ok_or_else(too_large)? {
+                Err(Error::SdlError("Pitch is smaller than a row of pixels.".to_owned()))
+            } else if data.len() < min_data_len(width, height, pitch, format).ok_or_else(too_large)? {
+                Err(Error::SdlError("Buffer is too small.".to_owned()))
+            } else {
+                let raw = sys::SDL_CreateRGBSurfaceWithFormatFrom(
+                    data.as_mut_ptr() as *mut _, width as c_int, height as c_int,
+                    bits_per_pixel(format), pitch as c_int, format as u32);
+
+                if raw.is_null() {
+                    Err(get_error_as_error())
+                } else {
+                    Ok(Surface::from_ll(raw))
+                }
+            }
+        }
     }
 
     /// Creates a new surface from an existing buffer, using pixel masks.
@@ -287,6 +323,51 @@ impl SurfaceRef {
         }
     }
 
+    /// Reads the color of the pixel at `(x, y)`, like `SDL_GetRGBA`.
+    ///
+    /// The surface is locked if needed. Returns `None` if the pixel is outside of the surface.
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Color> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let layout = PixelLayout::of(self);
+        let pitch = self.pitch() as usize;
+        let value = self.with_lock(|pixels| layout.read(&pixels[y as usize * pitch..], x as usize));
+        Some(Color::from_u32(&self.pixel_format(), value))
+    }
+
+    /// Sets the pixel at `(x, y)` to the closest color available in the surface format, like
+    /// `SDL_MapRGBA`.
+    ///
+    /// The surface is locked if needed. Errors if the pixel is outside of the surface.
+    pub fn put_pixel(&mut self, x: u32, y: u32, color: Color) -> Result<(), Error> {
+        if x >= self.width() || y >= self.height() {
+            return Err(Error::SdlError(format!(
+                "Pixel ({}, {}) is outside of the {}x{} surface", x, y, self.width(), self.height())));
+        }
+        let layout = PixelLayout::of(self);
+        let pitch = self.pitch() as usize;
+        let value = color.to_u32(&self.pixel_format());
+        self.with_lock_mut(|pixels| layout.write(&mut pixels[y as usize * pitch..], x as usize, value));
+        Ok(())
+    }
+
+    /// Iterates over the pixels of the surface, row by row, giving `(x, y, color)`.
+    ///
+    /// The surface stays locked while the iterator is alive.
+    pub fn pixels_iter(&self) -> SurfacePixels<'_> {
+        unsafe {
+            if sys::SDL_LockSurface(self.raw()) != 0 { panic!("could not lock surface"); }
+        }
+        SurfacePixels {
+            surface: self,
+            format: self.pixel_format(),
+            layout: PixelLayout::of(self),
+            x: 0,
+            y: 0,
+        }
+    }
+
     /// Returns the Surface's pixel buffer if the Surface doesn't require locking
     /// (e.g. it's a software surface).
     pub fn without_lock(&self) -> Option<&[u8]> {
@@ -336,6 +417,14 @@ impl SurfaceRef {
         self.save_bmp_rw(&mut file)
     }
 
+    /// Returns a copy of the palette of an indexed surface, or `None` for the other surfaces.
+    ///
+    /// To change the colors of the surface, modify the copy and set it back with
+    /// `set_palette`.
+    pub fn palette(&self) -> Option<pixels::Palette> {
+        self.pixel_format().palette()
+    }
+
     pub fn set_palette(&mut self, palette: &pixels::Palette) -> Result<(), Error> {
         let result = unsafe { sys::SDL_SetSurfacePalette(self.raw(), palette.raw()) };
 
@@ -479,7 +568,7 @@ impl SurfaceRef {
     /// The function will fail if the blend mode is not supported by SDL.
     pub fn set_blend_mode(&mut self, mode: BlendMode) -> Result<(), Error> {
         let result = unsafe {
-            sys::SDL_SetSurfaceBlendMode(self.raw(), transmute(mode))
+            blend_ffi::SDL_SetSurfaceBlendMode(self.raw(), mode as u32)
         };
 
         match result {
@@ -489,13 +578,14 @@ impl SurfaceRef {
     }
 
     pub fn blend_mode(&self) -> BlendMode {
-        let mut mode = sys::SDL_BlendMode::SDL_BLENDMODE_NONE;
+        let mut mode = 0;
         let result = unsafe {
-            sys::SDL_GetSurfaceBlendMode(self.raw(), &mut mode)
+            blend_ffi::SDL_GetSurfaceBlendMode(self.raw(), &mut mode)
         };
 
         match result {
-            0 => FromPrimitive::from_i32(mode as i32).unwrap(),
+            // Surfaces only support the predefined blend modes
+            0 => FromPrimitive::from_u32(mode).unwrap(),
             // Should only fail on a null Surface
             _ => panic!(get_error())
         }
@@ -557,6 +647,154 @@ impl SurfaceRef {
         }
     }
 
+    /// Copies the surface into a new one with the same pixel format, palette and blending
+    /// settings.
+    pub fn duplicate(&self) -> Result<Surface<'static>, Error> {
+        let surface_ptr = unsafe { sys::SDL_DuplicateSurface(self.raw()) };
+
+        if surface_ptr.is_null() {
+            Err(get_error_as_error())
+        } else {
+            unsafe { Ok(Surface::from_ll(surface_ptr)) }
+        }
+    }
+
+    /// Returns a copy of the surface, mirrored left to right.
+    ///
+    /// Like the other geometric transforms, this works on surfaces of any pixel format with at
+    /// least 8 bits per pixel, without the `gfx` feature. The copy keeps the palette, color key
+    /// and blending settings of the surface.
+    pub fn flip_horizontal(&self) -> Result<Surface<'static>, Error> {
+        let width = self.width() as usize;
+        self.remap(self.size(), |x, y| (width - 1 - x, y))
+    }
+
+    /// Returns a copy of the surface, mirrored top to bottom.
+    pub fn flip_vertical(&self) -> Result<Surface<'static>, Error> {
+        let height = self.height() as usize;
+        self.remap(self.size(), |x, y| (x, height - 1 - y))
+    }
+
+    /// Returns a copy of the surface, rotated clockwise by `turns` quarter turns.
+    ///
+    /// Negative values rotate counterclockwise.
+    pub fn rotate90(&self, turns: i32) -> Result<Surface<'static>, Error> {
+        let (width, height) = (self.width() as usize, self.height() as usize);
+        let rotated_size = (self.height(), self.width());
+        match turns.rem_euclid(4) {
+            0 => self.remap(self.size(), |x, y| (x, y)),
+            1 => self.remap(rotated_size, |x, y| (y, height - 1 - x)),
+            2 => self.remap(self.size(), |x, y| (width - 1 - x, height - 1 - y)),
+            _ => self.remap(rotated_size, |x, y| (width - 1 - y, x)),
+        }
+    }
+
+    /// Copies the pixels inside `rect` into a new surface of the size of `rect`.
+    ///
+    /// Errors if `rect` is not entirely inside the surface.
+    pub fn sub_surface(&self, rect: Rect) -> Result<Surface<'static>, Error> {
+        if !self.rect().contains_rect(rect) {
+            return Err(Error::SdlError(format!(
+                "{:?} is not inside the {}x{} surface", rect, self.width(), self.height())));
+        }
+        let (left, top) = (rect.x() as usize, rect.y() as usize);
+        self.remap(rect.size(), |x, y| (x + left, y + top))
+    }
+
+    /// Creates a surface of the given size, filled by copying the pixel at `source(x, y)` of
+    /// this surface to `(x, y)`.
+    fn remap<F>(&self, size: (u32, u32), source: F) -> Result<Surface<'static>, Error>
+        where F: Fn(usize, usize) -> (usize, usize)
+    {
+        let layout = PixelLayout::of(self);
+        if layout.bits < 8 {
+            return Err(Error::SdlError(format!(
+                "{:?} surfaces cannot be transformed, pixels must be at least a byte",
+                self.pixel_format_enum())));
+        }
+        let mut surface = self.blank_copy(size.0, size.1)?;
+        let (src_pitch, dst_pitch) = (self.pitch() as usize, surface.pitch() as usize);
+        let dst_size = (size.0 as usize, size.1 as usize);
+        self.with_lock(|src| {
+            surface.with_lock_mut(|dst| {
+                remap_pixels(src, src_pitch, dst, dst_pitch, layout.bytes, dst_size, source)
+            })
+        });
+        Ok(surface)
+    }
+
+    /// Borrows the pixels inside `rect` as a surface, without copying them.
+    ///
+    /// The view uses the pitch of this surface and shares its palette, and drawing to the view
+    /// draws to this surface. This makes it cheap to slice a sprite sheet, for instance to pass
+    /// each sprite to `TextureCreator::create_texture_from_surface`.
+    ///
+    /// Errors if `rect` is not entirely inside the surface, if the surface is RLE accelerated,
+    /// or if `rect` does not start on a byte boundary (for formats with less than 8 bits per
+    /// pixel).
+    pub fn view(&mut self, rect: Rect) -> Result<Surface<'_>, Error> {
+        if !self.rect().contains_rect(rect) {
+            return Err(Error::SdlError(format!(
+                "{:?} is not inside the {}x{} surface", rect, self.width(), self.height())));
+        }
+        let bits = PixelLayout::of(self).bits;
+        if (rect.x() as usize * bits) & 7 != 0 {
+            return Err(Error::SdlError(format!(
+                "{:?} does not start on a byte boundary", rect)));
+        }
+        let offset = rect.y() as usize * self.pitch() as usize + rect.x() as usize * bits / 8;
+        let (pitch, format) = (self.pitch(), self.pixel_format_enum());
+        let parent = self.raw();
+
+        let data = match self.without_lock_mut() {
+            Some(data) => data,
+            None => return Err(Error::SdlError(
+                "The pixels of an RLE accelerated surface cannot be viewed".to_owned())),
+        };
+        let mut view = Surface::from_data(&mut data[offset..], rect.width(), rect.height(), pitch, format)?;
+        unsafe {
+            let palette = (*(*parent).format).palette;
+            // The palette is reference counted, so it stays shared with this surface
+            if !palette.is_null() && sys::SDL_SetSurfacePalette(view.raw(), palette) != 0 {
+                return Err(get_error_as_error());
+            }
+            SurfaceRef::from_ll(parent).copy_blit_settings(&mut view)?;
+        }
+        Ok(view)
+    }
+
+    /// Creates a surface of the given size with the pixel format, palette, color key and
+    /// blending settings of this surface.
+    fn blank_copy(&self, width: u32, height: u32) -> Result<Surface<'static>, Error> {
+        let mut surface = Surface::new(width, height, self.pixel_format_enum())?;
+        unsafe {
+            let src_palette = (*self.raw_ref().format).palette;
+            let dst_palette = (*surface.raw_ref().format).palette;
+            if !src_palette.is_null() && !dst_palette.is_null() {
+                let ncolors = (*src_palette).ncolors.min((*dst_palette).ncolors);
+                if sys::SDL_SetPaletteColors(dst_palette, (*src_palette).colors, 0, ncolors) != 0 {
+                    return Err(get_error_as_error());
+                }
+            }
+        }
+        self.copy_blit_settings(&mut surface)?;
+        Ok(surface)
+    }
+
+    /// Copies the color key and the blending settings of this surface to `dst`.
+    fn copy_blit_settings(&self, dst: &mut SurfaceRef) -> Result<(), Error> {
+        let mut key = 0;
+        unsafe {
+            if sys::SDL_GetColorKey(self.raw(), &mut key) == 0 {
+                sys::SDL_SetColorKey(dst.raw(), 1, key);
+            }
+        }
+        dst.set_blend_mode(self.blend_mode())?;
+        dst.set_alpha_mod(self.alpha_mod());
+        dst.set_color_mod(self.color_mod());
+        Ok(())
+    }
+
     /// Performs surface blitting (surface copying).
     ///
     /// Returns the final blit rectangle, if a `dst_rect` was provided.
@@ -663,7 +901,221 @@ impl SurfaceRef {
         }
     }
 
-    /*
-    pub fn SDL_ConvertPixels(width: c_int, height: c_int, src_format: uint32_t, src: *c_void, src_pitch: c_int, dst_format: uint32_t, dst: *c_void, dst_pitch: c_int) -> c_int;
-    */
+    /// Copies a rectangle to a rectangle of another size on `dst` with nearest-neighbor
+    /// scaling, like `SDL_SoftStretch`.
+    ///
+    /// Unlike `blit_scaled`, no blending or format conversion is done: both surfaces must have
+    /// the same pixel format, and the rectangles must be inside the surfaces.
+    pub fn soft_stretch<R1, R2>(&self, src_rect: R1,
+                                dst: &mut SurfaceRef, dst_rect: R2) -> Result<(), Error>
+    where R1: Into<Option<Rect>>,
+          R2: Into<Option<Rect>>,
+    {
+        let src_rect = src_rect.into();
+        let dst_rect = dst_rect.into();
+        let src_rect_ptr = src_rect.as_ref().map(|r| r.raw()).unwrap_or(ptr::null());
+        let dst_rect_ptr = dst_rect.as_ref().map(|r| r.raw()).unwrap_or(ptr::null());
+
+        let result = unsafe {
+            sys::SDL_SoftStretch(self.raw(), src_rect_ptr, dst.raw(), dst_rect_ptr)
+        };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(get_error_as_error())
+        }
+    }
+}
+
+/// The number of bits per pixel of a format, as given to `SDL_CreateRGBSurfaceWithFormat`.
+fn bits_per_pixel(format: pixels::PixelFormatEnum) -> c_int {
+    ((format as u32 >> 8) & 0xFF) as c_int
+}
+
+/// Fills `dst`, a buffer of `dst_size` pixels of `bytes` bytes, with the pixels of `src` at the
+/// positions given by `source`.
+fn remap_pixels<F>(src: &[u8], src_pitch: usize, dst: &mut [u8], dst_pitch: usize,
+                   bytes: usize, dst_size: (usize, usize), source: F)
+    where F: Fn(usize, usize) -> (usize, usize)
+{
+    let (width, height) = dst_size;
+    for y in 0..height {
+        let row = &mut dst[y * dst_pitch..][..width * bytes];
+        for (x, pixel) in row.chunks_mut(bytes).enumerate() {
+            let (src_x, src_y) = source(x, y);
+            pixel.copy_from_slice(&src[src_y * src_pitch + src_x * bytes..][..bytes]);
+        }
+    }
+}
+
+/// The number of bytes used by a row of `width` pixels, or `None` if it overflows `usize`.
+fn row_bytes(width: u32, format: pixels::PixelFormatEnum) -> Option<usize> {
+    let row_bits = (width as usize).checked_mul(bits_per_pixel(format) as usize)?;
+    Some(row_bits / 8 + if row_bits & 7 != 0 { 1 } else { 0 })
+}
+
+/// The number of bytes needed to hold `height` rows of `width` pixels, with `pitch` bytes
+/// between the start of two rows, or `None` if it overflows `usize`.
+fn min_data_len(width: u32, height: u32, pitch: u32, format: pixels::PixelFormatEnum)
+                -> Option<usize> {
+    if height == 0 {
+        return Some(0);
+    }
+    (pitch as usize).checked_mul(height as usize - 1)?.checked_add(row_bytes(width, format)?)
+}
+
+/// How the pixel values are stored in the rows of a surface.
+#[derive(Copy, Clone, Debug)]
+struct PixelLayout {
+    bits: usize,
+    bytes: usize,
+    /// For formats with several pixels per byte, whether the first pixel is in the high bits.
+    msb_first: bool,
+}
+
+impl PixelLayout {
+    fn of(surface: &SurfaceRef) -> PixelLayout {
+        let format = surface.pixel_format_enum();
+        let (bits, bytes) = unsafe {
+            let format = &*surface.raw_ref().format;
+            (format.BitsPerPixel as usize, format.BytesPerPixel as usize)
+        };
+        PixelLayout {
+            bits,
+            bytes,
+            msb_first: format == pixels::PixelFormatEnum::Index1MSB ||
+                       format == pixels::PixelFormatEnum::Index4MSB,
+        }
+    }
+
+    /// The position of pixel `x` in a row with less than 8 bits per pixel, as `(byte, shift)`.
+    fn bit_position(&self, x: usize) -> (usize, usize) {
+        let offset = x * self.bits % 8;
+        let shift = if self.msb_first { 8 - self.bits - offset } else { offset };
+        (x * self.bits / 8, shift)
+    }
+
+    fn read(&self, row: &[u8], x: usize) -> u32 {
+        if self.bits < 8 {
+            let (byte, shift) = self.bit_position(x);
+            return u32::from(row[byte] >> shift) & ((1 << self.bits) - 1);
+        }
+        let size = self.bytes;
+        let mut value = [0; 4];
+        // SDL stores the pixels of 1, 2 and 3 bytes in the low-order bytes of the value.
+        if cfg!(target_endian = "big") {
+            value[4 - size..].copy_from_slice(&row[x * size..(x + 1) * size]);
+        } else {
+            value[..size].copy_from_slice(&row[x * size..(x + 1) * size]);
+        }
+        u32::from_ne_bytes(value)
+    }
+
+    fn write(&self, row: &mut [u8], x: usize, value: u32) {
+        if self.bits < 8 {
+            let (byte, shift) = self.bit_position(x);
+            let mask = (((1u32 << self.bits) - 1) << shift) as u8;
+            row[byte] = (row[byte] & !mask) | ((value << shift) as u8 & mask);
+            return;
+        }
+        let size = self.bytes;
+        let value = value.to_ne_bytes();
+        let value = if cfg!(target_endian = "big") { &value[4 - size..] } else { &value[..size] };
+        row[x * size..(x + 1) * size].copy_from_slice(value);
+    }
+}
+
+/// An iterator over the pixels of a surface, created by `SurfaceRef::pixels_iter`.
+pub struct SurfacePixels<'a> {
+    surface: &'a SurfaceRef,
+    format: pixels::PixelFormat,
+    layout: PixelLayout,
+    x: u32,
+    y: u32,
+}
+
+impl<'a> Iterator for SurfacePixels<'a> {
+    type Item = (u32, u32, Color);
+
+    fn next(&mut self) -> Option<(u32, u32, Color)> {
+        if self.x >= self.surface.width() {
+            self.x = 0;
+            self.y += 1;
+        }
+        if self.y >= self.surface.height() {
+            return None;
+        }
+        let (x, y) = (self.x, self.y);
+        self.x += 1;
+
+        let raw = self.surface.raw_ref();
+        let value = unsafe {
+            let row = ::std::slice::from_raw_parts(
+                (raw.pixels as *const u8).offset(y as isize * raw.pitch as isize),
+                raw.pitch as usize);
+            self.layout.read(row, x as usize)
+        };
+        Some((x, y, Color::from_u32(&self.format, value)))
+    }
+}
+
+impl<'a> Drop for SurfacePixels<'a> {
+    fn drop(&mut self) {
+        unsafe { sys::SDL_UnlockSurface(self.surface.raw()) }
+    }
+}
+
+#[test]
+fn pixel_layout_read_write() {
+    let rgb24 = PixelLayout { bits: 24, bytes: 3, msb_first: false };
+    let mut row = [0u8; 6];
+    rgb24.write(&mut row, 1, 0x00A0_B0C0);
+    assert_eq!(rgb24.read(&row, 1), 0x00A0_B0C0);
+    assert_eq!(rgb24.read(&row, 0), 0);
+
+    let index1 = PixelLayout { bits: 1, bytes: 1, msb_first: true };
+    let mut row = [0u8; 2];
+    index1.write(&mut row, 0, 1);
+    index1.write(&mut row, 9, 1);
+    assert_eq!(row, [0b1000_0000, 0b0100_0000]);
+    assert_eq!(index1.read(&row, 9), 1);
+    assert_eq!(index1.read(&row, 8), 0);
+
+    let index4 = PixelLayout { bits: 4, bytes: 1, msb_first: false };
+    let mut row = [0xFFu8];
+    index4.write(&mut row, 1, 0x3);
+    assert_eq!(row, [0x3F]);
+    assert_eq!(index4.read(&row, 0), 0xF);
+}
+
+#[test]
+fn remap_pixels_rotate() {
+    // A 3x2 image of 2-byte pixels, with one byte of padding per row.
+    let src = [1, 1, 2, 2, 3, 3, 0,
+               4, 4, 5, 5, 6, 6, 0];
+    let mut dst = [0u8; 12];
+    // Clockwise quarter turn, giving a 2x3 image.
+    remap_pixels(&src, 7, &mut dst, 4, 2, (2, 3), |x, y| (y, 1 - x));
+    assert_eq!(dst, [4, 4, 1, 1,
+                     5, 5, 2, 2,
+                     6, 6, 3, 3]);
+
+    let mut dst = [0u8; 6];
+    remap_pixels(&src, 7, &mut dst, 6, 2, (3, 1), |x, y| (2 - x, y + 1));
+    assert_eq!(dst, [6, 6, 5, 5, 4, 4]);
+}
+
+#[test]
+fn data_len_excludes_last_padding() {
+    use crate::pixels::PixelFormatEnum;
+
+    assert_eq!(min_data_len(4, 2, 16, PixelFormatEnum::RGB24), Some(16 + 12));
+    assert_eq!(min_data_len(9, 3, 4, PixelFormatEnum::Index1MSB), Some(8 + 2));
+    assert_eq!(min_data_len(4, 0, 16, PixelFormatEnum::RGB24), Some(0));
+    assert_eq!(row_bytes(9, PixelFormatEnum::Index1MSB), Some(2));
+    assert_eq!(row_bytes(4, PixelFormatEnum::ARGB8888), Some(16));
+    if cfg!(target_pointer_width = "32") {
+        assert_eq!(row_bytes(1 << 30, PixelFormatEnum::ARGB8888), None);
+        assert_eq!(min_data_len(1, 1 << 30, (1 << 31) - 1, PixelFormatEnum::Index8), None);
+    }
 }
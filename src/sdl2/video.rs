@@ -1,11 +1,11 @@
-use libc::{c_int, c_uint, c_float, c_char};
+use libc::{c_int, c_uint, c_ulong, c_float, c_char, c_void};
 use std::ffi::{CStr, CString, NulError};
 use std::{mem, ptr, fmt};
 use std::rc::Rc;
 use std::error::Error as StdError;
 use std::ops::{Deref, DerefMut};
 
-use crate::rect::Rect;
+use crate::rect::{Point, Rect};
 use crate::render::CanvasBuilder;
 use crate::surface::SurfaceRef;
 use crate::pixels::PixelFormatEnum;
@@ -89,6 +89,17 @@ pub enum GLProfile {
     Unknown(i32)
 }
 
+/// What happens to pending commands when an OpenGL context stops being current.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum GLContextReleaseBehavior {
+    /// Pending commands are not flushed
+    None,
+    /// Pending commands are flushed; this is the default
+    Flush,
+    /// Unknown behavior, as returned by SDL
+    Unknown(i32)
+}
+
 trait GLAttrTypeUtil {
     fn to_gl_value(self) -> i32;
     fn from_gl_value(value: i32) -> Self;
@@ -127,6 +138,27 @@ impl GLAttrTypeUtil for GLProfile {
     }
 }
 
+impl GLAttrTypeUtil for GLContextReleaseBehavior {
+    fn to_gl_value(self) -> i32 {
+        use self::GLContextReleaseBehavior::*;
+
+        match self {
+            Unknown(i) => i,
+            None => sys::SDL_GLcontextReleaseFlag::SDL_GL_CONTEXT_RELEASE_BEHAVIOR_NONE as i32,
+            Flush => sys::SDL_GLcontextReleaseFlag::SDL_GL_CONTEXT_RELEASE_BEHAVIOR_FLUSH as i32,
+        }
+    }
+    fn from_gl_value(value: i32) -> GLContextReleaseBehavior {
+        use self::GLContextReleaseBehavior::*;
+
+        match value {
+            0 => None,
+            1 => Flush,
+            i => Unknown(i),
+        }
+    }
+}
+
 macro_rules! attrs {
     (
         $(($attr_name:ident, $set_property:ident, $get_property:ident, $t:ty, $doc:expr)),*
@@ -185,7 +217,7 @@ pub mod gl_attr {
     use crate::sys;
     use crate::get_error;
     use std::marker::PhantomData;
-    use super::{GLProfile, GLAttrTypeUtil};
+    use super::{GLProfile, GLContextReleaseBehavior, GLAttrTypeUtil};
 
     /// OpenGL context getters and setters. Obtain with `VideoSubsystem::gl_attr()`.
     pub struct GLAttr<'a> {
@@ -294,7 +326,19 @@ pub mod gl_attr {
             "OpenGL context sharing; defaults to false"),
 
         (SDL_GL_FRAMEBUFFER_SRGB_CAPABLE, set_framebuffer_srgb_compatible, framebuffer_srgb_compatible, bool,
-            "requests sRGB capable visual; defaults to false (>= SDL 2.0.1)")
+            "requests sRGB capable visual; defaults to false (>= SDL 2.0.1)"),
+
+        (SDL_GL_CONTEXT_RELEASE_BEHAVIOR, set_context_release_behavior, context_release_behavior, GLContextReleaseBehavior,
+            "whether pending commands are flushed when the context is released; defaults to Flush (>= SDL 2.0.4)"),
+
+        (SDL_GL_CONTEXT_NO_ERROR, set_context_no_error, context_no_error, bool,
+            "requests a context which does not report errors (KHR_no_error); defaults to false (>= SDL 2.0.6)")
+    }
+
+    /// Resets all the attributes to their default values (see `SDL_GL_ResetAttributes`).
+    #[inline]
+    pub fn reset(&self) {
+        unsafe { sys::SDL_GL_ResetAttributes() }
     }
 
     /// **Sets** the OpenGL context major and minor versions.
@@ -454,6 +498,181 @@ impl DisplayMode {
     }
 }
 
+/// The orientation of a display (>= SDL 2.0.9).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum DisplayOrientation {
+    /// The display orientation can't be determined
+    Unknown,
+    /// The display is in landscape mode, with the right side up, relative to portrait mode
+    Landscape,
+    /// The display is in landscape mode, with the left side up, relative to portrait mode
+    LandscapeFlipped,
+    /// The display is in portrait mode
+    Portrait,
+    /// The display is in portrait mode, upside down
+    PortraitFlipped,
+}
+
+impl DisplayOrientation {
+    pub fn from_ll(orientation: sys::SDL_DisplayOrientation) -> DisplayOrientation {
+        use crate::sys::SDL_DisplayOrientation::*;
+
+        match orientation {
+            SDL_ORIENTATION_UNKNOWN => DisplayOrientation::Unknown,
+            SDL_ORIENTATION_LANDSCAPE => DisplayOrientation::Landscape,
+            SDL_ORIENTATION_LANDSCAPE_FLIPPED => DisplayOrientation::LandscapeFlipped,
+            SDL_ORIENTATION_PORTRAIT => DisplayOrientation::Portrait,
+            SDL_ORIENTATION_PORTRAIT_FLIPPED => DisplayOrientation::PortraitFlipped,
+        }
+    }
+
+    pub fn to_ll(self) -> sys::SDL_DisplayOrientation {
+        use crate::sys::SDL_DisplayOrientation::*;
+
+        match self {
+            DisplayOrientation::Unknown => SDL_ORIENTATION_UNKNOWN,
+            DisplayOrientation::Landscape => SDL_ORIENTATION_LANDSCAPE,
+            DisplayOrientation::LandscapeFlipped => SDL_ORIENTATION_LANDSCAPE_FLIPPED,
+            DisplayOrientation::Portrait => SDL_ORIENTATION_PORTRAIT,
+            DisplayOrientation::PortraitFlipped => SDL_ORIENTATION_PORTRAIT_FLIPPED,
+        }
+    }
+}
+
+/// A handle to a display (monitor) connected to the system.
+///
+/// Obtain with `VideoSubsystem::display()`, `VideoSubsystem::displays()`,
+/// `VideoSubsystem::display_containing_point()` or `Window::display()`.
+///
+/// A `Display` only remembers the index of the display, like the `display_index` arguments of the
+/// `VideoSubsystem` methods do: if monitors are connected or disconnected, the same index may
+/// point to another display, or to none at all, in which case the methods return an error.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::video::WindowPos;
+///
+/// let sdl_context = sdl2::init().unwrap();
+/// let video_subsystem = sdl_context.video().unwrap();
+/// let mut window = video_subsystem.window("rust-sdl2 demo: Display", 800, 600).build().unwrap();
+///
+/// // Move the window to the top-left corner of the space usable on its display
+/// let usable = window.display().unwrap().usable_bounds().unwrap();
+/// window.set_position(WindowPos::Positioned(usable.x()), WindowPos::Positioned(usable.y()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Display {
+    subsystem: VideoSubsystem,
+    index: i32,
+}
+
+impl Display {
+    /// The index of this display, as used by the `display_*` methods of `VideoSubsystem`.
+    #[inline]
+    pub fn index(&self) -> i32 { self.index }
+
+    #[inline]
+    pub fn subsystem(&self) -> &VideoSubsystem { &self.subsystem }
+
+    /// Get the name of the display.
+    pub fn name(&self) -> Result<String, Error> {
+        self.subsystem.display_name(self.index)
+    }
+
+    /// Get the desktop area represented by the display, with the primary display located at
+    /// `(0, 0)`.
+    pub fn bounds(&self) -> Result<Rect, Error> {
+        self.subsystem.display_bounds(self.index)
+    }
+
+    /// Get the usable desktop area represented by the display, i.e. the bounds minus areas
+    /// reserved by the OS such as task bars and menu bars.
+    pub fn usable_bounds(&self) -> Result<Rect, Error> {
+        self.subsystem.display_usable_bounds(self.index)
+    }
+
+    /// Return a triplet `(ddpi, hdpi, vdpi)` containing the diagonal, horizontal and vertical
+    /// dots/pixels-per-inch of the display
+    pub fn dpi(&self) -> Result<(f32, f32, f32), Error> {
+        self.subsystem.display_dpi(self.index)
+    }
+
+    pub fn orientation(&self) -> DisplayOrientation {
+        self.subsystem.display_orientation(self.index)
+    }
+
+    pub fn num_modes(&self) -> Result<i32, Error> {
+        self.subsystem.num_display_modes(self.index)
+    }
+
+    /// Get an iterator over the display modes available on the display, sorted from the largest
+    /// to the smallest as SDL does.
+    pub fn modes(&self) -> Result<DisplayModeIterator, Error> {
+        let length = self.num_modes()?;
+        Ok(DisplayModeIterator {
+            display_index: self.index,
+            length,
+            index: 0,
+        })
+    }
+
+    pub fn mode(&self, mode_index: i32) -> Result<DisplayMode, Error> {
+        self.subsystem.display_mode(self.index, mode_index)
+    }
+
+    pub fn desktop_mode(&self) -> Result<DisplayMode, Error> {
+        self.subsystem.desktop_display_mode(self.index)
+    }
+
+    pub fn current_mode(&self) -> Result<DisplayMode, Error> {
+        self.subsystem.current_display_mode(self.index)
+    }
+
+    pub fn closest_mode(&self, mode: &DisplayMode) -> Result<DisplayMode, Error> {
+        self.subsystem.closest_display_mode(self.index, mode)
+    }
+}
+
+/// An iterator over the display modes of a `Display`. Obtain with `Display::modes()`.
+///
+/// The iteration stops early if a mode can not be queried anymore, which happens if the display
+/// is disconnected while iterating.
+#[derive(Copy, Clone, Debug)]
+pub struct DisplayModeIterator {
+    display_index: i32,
+    length: i32,
+    index: i32,
+}
+
+impl Iterator for DisplayModeIterator {
+    type Item = DisplayMode;
+
+    fn next(&mut self) -> Option<DisplayMode> {
+        if self.index >= self.length {
+            return None;
+        }
+
+        let mut dm = mem::MaybeUninit::uninit();
+        let result = unsafe {
+            sys::SDL_GetDisplayMode(self.display_index as c_int, self.index as c_int, dm.as_mut_ptr()) == 0
+        };
+
+        if result {
+            self.index += 1;
+            let dm = unsafe { dm.assume_init() };
+            Some(DisplayMode::from_ll(&dm))
+        } else {
+            self.index = self.length;
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.length - self.index) as usize))
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum FullscreenType {
     Off = 0,
@@ -508,6 +727,260 @@ impl GLContext {
     }
 }
 
+fn gl_get_attribute(attr: sys::SDL_GLattr) -> Result<i32, String> {
+    let mut value = 0;
+    if unsafe { sys::SDL_GL_GetAttribute(attr, &mut value) } == 0 {
+        Ok(value)
+    } else {
+        Err(get_error())
+    }
+}
+
+fn gl_set_attribute(attr: sys::SDL_GLattr, value: i32) -> Result<(), String> {
+    if unsafe { sys::SDL_GL_SetAttribute(attr, value) } == 0 {
+        Ok(())
+    } else {
+        Err(get_error())
+    }
+}
+
+#[derive(Debug)]
+pub enum GLContextBuildError {
+    /// The context was created, but an attribute does not match the requested one.
+    AttributeMismatch { attribute: &'static str, requested: i32, obtained: i32 },
+    SdlError(String),
+}
+
+impl fmt::Display for GLContextBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::GLContextBuildError::*;
+
+        match *self {
+            AttributeMismatch { attribute, requested, obtained } =>
+                write!(f, "OpenGL attribute {} is {}, but {} was requested", attribute, obtained, requested),
+            SdlError(ref e) => write!(f, "SDL error: {}", e),
+        }
+    }
+}
+
+impl StdError for GLContextBuildError {
+    fn description(&self) -> &str {
+        use self::GLContextBuildError::*;
+
+        match *self {
+            AttributeMismatch { .. } => "OpenGL attribute mismatch",
+            SdlError(ref e) => e,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct GLAttrRequirement {
+    attr: sys::SDL_GLattr,
+    name: &'static str,
+    value: i32,
+    exact: bool,
+}
+
+/// The type that allows you to build OpenGL contexts.
+///
+/// Context attributes (version, profile, flags, ...) are only set while the context is created:
+/// the previous values of the `GLAttr` attributes are restored afterwards.
+///
+/// Framebuffer attributes (color, depth and stencil sizes, multisampling, ...) are chosen when an
+/// OpenGL window is created, so they have to be set with `GLAttr` beforehand. The builder
+/// checks that the context obtained satisfies the ones given here, and returns
+/// `GLContextBuildError::AttributeMismatch` otherwise.
+///
+/// Like `Window::gl_create_context`, a successful `build` makes the new context current.
+///
+/// # Example
+/// ```no_run
+/// use sdl2::video::{GLContextBuilder, GLProfile};
+///
+/// let sdl_context = sdl2::init().unwrap();
+/// let video_subsystem = sdl_context.video().unwrap();
+/// video_subsystem.gl_attr().set_depth_size(24);
+/// let window = video_subsystem.window("rust-sdl2 demo", 800, 600).opengl().build().unwrap();
+///
+/// let context = GLContextBuilder::new()
+///     .version(3, 3)
+///     .profile(GLProfile::Core)
+///     .min_depth_size(24)
+///     .build(&window)
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct GLContextBuilder<'a> {
+    context_attrs: Vec<(sys::SDL_GLattr, i32)>,
+    flags: Option<i32>,
+    requirements: Vec<GLAttrRequirement>,
+    share_with: Option<&'a GLContext>,
+}
+
+impl<'a> GLContextBuilder<'a> {
+    /// Initializes a new `GLContextBuilder`.
+    pub fn new() -> GLContextBuilder<'a> {
+        GLContextBuilder::default()
+    }
+
+    fn context_attr(&mut self, attr: sys::SDL_GLattr, value: i32) -> &mut GLContextBuilder<'a> {
+        self.context_attrs.retain(|&(a, _)| a != attr);
+        self.context_attrs.push((attr, value));
+        self
+    }
+
+    fn context_flag(&mut self, flag: i32) -> &mut GLContextBuilder<'a> {
+        self.flags = Some(self.flags.unwrap_or(0) | flag);
+        self
+    }
+
+    fn require(&mut self, attr: sys::SDL_GLattr, name: &'static str, value: i32, exact: bool) -> &mut GLContextBuilder<'a> {
+        self.requirements.retain(|r| r.attr != attr);
+        self.requirements.push(GLAttrRequirement { attr, name, value, exact });
+        self
+    }
+
+    /// Requests an OpenGL context version.
+    pub fn version(&mut self, major: u8, minor: u8) -> &mut GLContextBuilder<'a> {
+        self.context_attr(sys::SDL_GLattr::SDL_GL_CONTEXT_MAJOR_VERSION, major.to_gl_value());
+        self.context_attr(sys::SDL_GLattr::SDL_GL_CONTEXT_MINOR_VERSION, minor.to_gl_value())
+    }
+
+    /// Requests an OpenGL profile.
+    pub fn profile(&mut self, profile: GLProfile) -> &mut GLContextBuilder<'a> {
+        self.context_attr(sys::SDL_GLattr::SDL_GL_CONTEXT_PROFILE_MASK, profile.to_gl_value())
+    }
+
+    /// Requests a "debug" context.
+    pub fn debug(&mut self) -> &mut GLContextBuilder<'a> {
+        self.context_flag(0x0001)
+    }
+
+    /// Requests a "forward compatible" context.
+    pub fn forward_compatible(&mut self) -> &mut GLContextBuilder<'a> {
+        self.context_flag(0x0002)
+    }
+
+    pub fn robust_access(&mut self) -> &mut GLContextBuilder<'a> {
+        self.context_flag(0x0004)
+    }
+
+    pub fn reset_isolation(&mut self) -> &mut GLContextBuilder<'a> {
+        self.context_flag(0x0008)
+    }
+
+    /// Requests a context which does not report errors (`SDL_GL_CONTEXT_NO_ERROR`).
+    pub fn no_error(&mut self, no_error: bool) -> &mut GLContextBuilder<'a> {
+        self.context_attr(sys::SDL_GLattr::SDL_GL_CONTEXT_NO_ERROR, no_error.to_gl_value())
+    }
+
+    /// Sets what happens to pending commands when the context is released.
+    pub fn release_behavior(&mut self, behavior: GLContextReleaseBehavior) -> &mut GLContextBuilder<'a> {
+        self.context_attr(sys::SDL_GLattr::SDL_GL_CONTEXT_RELEASE_BEHAVIOR, behavior.to_gl_value())
+    }
+
+    /// Shares the objects (textures, buffers, ...) of `context` with the new context.
+    pub fn share_with(&mut self, context: &'a GLContext) -> &mut GLContextBuilder<'a> {
+        self.share_with = Some(context);
+        self
+    }
+
+    /// Requires at least these sizes for the color channels of the obtained context.
+    pub fn min_color_size(&mut self, red: u8, green: u8, blue: u8, alpha: u8) -> &mut GLContextBuilder<'a> {
+        self.require(sys::SDL_GLattr::SDL_GL_RED_SIZE, "red size", red.to_gl_value(), false);
+        self.require(sys::SDL_GLattr::SDL_GL_GREEN_SIZE, "green size", green.to_gl_value(), false);
+        self.require(sys::SDL_GLattr::SDL_GL_BLUE_SIZE, "blue size", blue.to_gl_value(), false);
+        self.require(sys::SDL_GLattr::SDL_GL_ALPHA_SIZE, "alpha size", alpha.to_gl_value(), false)
+    }
+
+    /// Requires at least this depth buffer size for the obtained context.
+    pub fn min_depth_size(&mut self, size: u8) -> &mut GLContextBuilder<'a> {
+        self.require(sys::SDL_GLattr::SDL_GL_DEPTH_SIZE, "depth size", size.to_gl_value(), false)
+    }
+
+    /// Requires at least this stencil buffer size for the obtained context.
+    pub fn min_stencil_size(&mut self, size: u8) -> &mut GLContextBuilder<'a> {
+        self.require(sys::SDL_GLattr::SDL_GL_STENCIL_SIZE, "stencil size", size.to_gl_value(), false)
+    }
+
+    /// Requires at least this number of multisample samples for the obtained context.
+    pub fn min_multisample_samples(&mut self, samples: u8) -> &mut GLContextBuilder<'a> {
+        self.require(sys::SDL_GLattr::SDL_GL_MULTISAMPLESAMPLES, "multisample samples", samples.to_gl_value(), false)
+    }
+
+    /// Requires the obtained context to be double buffered or not.
+    pub fn double_buffer(&mut self, double_buffer: bool) -> &mut GLContextBuilder<'a> {
+        self.require(sys::SDL_GLattr::SDL_GL_DOUBLEBUFFER, "double buffer", double_buffer.to_gl_value(), true)
+    }
+
+    /// Requires the obtained context to be hardware accelerated or not.
+    pub fn accelerated_visual(&mut self, accelerated: bool) -> &mut GLContextBuilder<'a> {
+        self.require(sys::SDL_GLattr::SDL_GL_ACCELERATED_VISUAL, "accelerated visual", accelerated.to_gl_value(), true)
+    }
+
+    /// Creates the context for `window`, and checks the obtained attributes.
+    pub fn build(&self, window: &Window) -> Result<GLContext, GLContextBuildError> {
+        use self::GLContextBuildError::*;
+
+        let mut attrs = self.context_attrs.clone();
+        if let Some(flags) = self.flags {
+            attrs.push((sys::SDL_GLattr::SDL_GL_CONTEXT_FLAGS, flags));
+        }
+        if let Some(context) = self.share_with {
+            if !context.is_current() {
+                window.gl_make_current(context).map_err(|e| SdlError(e.to_string()))?;
+            }
+            attrs.push((sys::SDL_GLattr::SDL_GL_SHARE_WITH_CURRENT_CONTEXT, 1));
+        }
+
+        let mut previous = Vec::with_capacity(attrs.len());
+        let mut result = Ok(());
+        for &(attr, value) in &attrs {
+            result = gl_get_attribute(attr).and_then(|old| {
+                previous.push((attr, old));
+                gl_set_attribute(attr, value)
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+        let raw = match result {
+            Ok(()) => unsafe { sys::SDL_GL_CreateContext(window.raw()) },
+            Err(_) => ptr::null_mut(),
+        };
+        let create_error = if raw.is_null() { Some(get_error()) } else { None };
+        for &(attr, old) in previous.iter().rev() {
+            // restoring a value SDL accepted before cannot fail
+            let _ = gl_set_attribute(attr, old);
+        }
+        if let Err(e) = result {
+            return Err(SdlError(e));
+        }
+        if let Some(e) = create_error {
+            return Err(SdlError(e));
+        }
+
+        let context = GLContext { raw };
+        for requirement in &self.requirements {
+            let obtained = gl_get_attribute(requirement.attr).map_err(SdlError)?;
+            let satisfied = if requirement.exact {
+                obtained == requirement.value
+            } else {
+                obtained >= requirement.value
+            };
+            if !satisfied {
+                return Err(AttributeMismatch {
+                    attribute: requirement.name,
+                    requested: requirement.value,
+                    obtained,
+                });
+            }
+        }
+        Ok(context)
+    }
+}
+
 /// Holds a `SDL_Window`
 ///
 /// When the `WindowContext` is dropped, it destroys the `SDL_Window`
@@ -519,6 +992,8 @@ pub struct WindowContext {
 impl Drop for WindowContext {
     #[inline]
     fn drop(&mut self) {
+        // For windows adopted with `SDL_CreateWindowFrom` (flagged `SDL_WINDOW_FOREIGN`),
+        // SDL only frees its own bookkeeping and leaves the native window to its owner.
         unsafe { sys::SDL_DestroyWindow(self.raw) };
     }
 }
@@ -534,6 +1009,75 @@ impl WindowContext {
     }
 }
 
+/// Native handles of a window, as returned by `SDL_GetWindowWMInfo`.
+///
+/// Obtain with `Window::wm_info()`. The handles are only valid while the `Window` is alive.
+/// The variant depends on the video driver SDL is running on, not only on the target platform:
+/// on Linux, a window can be either `X11` or `Wayland`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum WindowManagerInfo {
+    /// Win32: the `HWND`, `HDC` and `HINSTANCE` of the window
+    Windows { window: *mut c_void, hdc: *mut c_void, hinstance: *mut c_void },
+    /// WinRT: the `IInspectable*` of the `CoreWindow`
+    WinRT { window: *mut c_void },
+    /// X11: the `Display*` and the `Window` XID
+    X11 { display: *mut c_void, window: c_ulong },
+    /// DirectFB: the `IDirectFB*`, `IDirectFBWindow*` and `IDirectFBSurface*`
+    DirectFB { dfb: *mut c_void, window: *mut c_void, surface: *mut c_void },
+    /// Cocoa: the `NSWindow*`
+    Cocoa { window: *mut c_void },
+    /// UIKit: the `UIWindow*`
+    UIKit { window: *mut c_void },
+    /// Wayland: the `wl_display*`, `wl_surface*` and `wl_shell_surface*`
+    Wayland { display: *mut c_void, surface: *mut c_void, shell_surface: *mut c_void },
+    /// Android: the `ANativeWindow*` and `EGLSurface`
+    Android { window: *mut c_void, surface: *mut c_void },
+    /// Vivante: the `EGLNativeDisplayType` and `EGLNativeWindowType`
+    Vivante { display: *mut c_void, window: *mut c_void },
+    /// A windowing subsystem this crate does not know about, given as its `SDL_SYSWM_TYPE` value
+    Other(u32),
+}
+
+impl WindowManagerInfo {
+    /// Decodes the platform-specific union of a `SDL_SysWMinfo` filled by `SDL_GetWindowWMInfo`.
+    ///
+    /// # Safety
+    ///
+    /// `info` must have been successfully filled in by `SDL_GetWindowWMInfo`,
+    /// so that its `subsystem` field describes the active union member.
+    pub unsafe fn from_ll(info: &sys::SDL_SysWMinfo) -> WindowManagerInfo {
+        use crate::sys::SDL_SYSWM_TYPE::*;
+
+        // The pregenerated bindings only describe the X11 and Wayland members of the union, so
+        // read it as the array of pointers every member starts with. Newer SDL versions may also
+        // report subsystems that are not variants of `SDL_SYSWM_TYPE` yet.
+        let subsystem = *(&info.subsystem as *const sys::SDL_SYSWM_TYPE as *const u32);
+        let words = info.info.dummy.as_ptr() as *const *mut c_void;
+        let word = |i: usize| ptr::read_unaligned(words.add(i));
+
+        match subsystem {
+            s if s == SDL_SYSWM_WINDOWS as u32 =>
+                WindowManagerInfo::Windows { window: word(0), hdc: word(1), hinstance: word(2) },
+            s if s == SDL_SYSWM_WINRT as u32 => WindowManagerInfo::WinRT { window: word(0) },
+            s if s == SDL_SYSWM_X11 as u32 => WindowManagerInfo::X11 {
+                display: word(0),
+                window: ptr::read_unaligned(words.add(1) as *const c_ulong),
+            },
+            s if s == SDL_SYSWM_DIRECTFB as u32 =>
+                WindowManagerInfo::DirectFB { dfb: word(0), window: word(1), surface: word(2) },
+            s if s == SDL_SYSWM_COCOA as u32 => WindowManagerInfo::Cocoa { window: word(0) },
+            s if s == SDL_SYSWM_UIKIT as u32 => WindowManagerInfo::UIKit { window: word(0) },
+            s if s == SDL_SYSWM_WAYLAND as u32 =>
+                WindowManagerInfo::Wayland { display: word(0), surface: word(1), shell_surface: word(2) },
+            s if s == SDL_SYSWM_ANDROID as u32 =>
+                WindowManagerInfo::Android { window: word(0), surface: word(1) },
+            s if s == SDL_SYSWM_VIVANTE as u32 =>
+                WindowManagerInfo::Vivante { display: word(0), window: word(1) },
+            other => WindowManagerInfo::Other(other),
+        }
+    }
+}
+
 /// Represents a setting for vsync/swap interval.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 #[repr(i32)]
@@ -584,6 +1128,26 @@ impl VideoSubsystem {
         WindowBuilder::new(self, title, width, height)
     }
 
+    /// Wraps a window created outside of SDL, such as an X11 `Window` XID or a Win32 `HWND`,
+    /// into a `Window` (see `SDL_CreateWindowFrom`).
+    ///
+    /// The returned `Window` can be used like any other, e.g. to build a `Canvas`.
+    /// When it is dropped, SDL releases its own state for the window but leaves the native
+    /// window itself alive: the host application stays responsible for destroying it.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a valid native window of the running video driver, and must outlive
+    /// the returned `Window` and everything created from it.
+    pub unsafe fn window_from_native(&self, handle: *const c_void) -> Result<Window, Error> {
+        let raw = sys::SDL_CreateWindowFrom(handle);
+        if raw.is_null() {
+            Err(get_error_as_error())
+        } else {
+            Ok(Window::from_ll(self.clone(), raw))
+        }
+    }
+
     pub fn current_video_driver(&self) -> &'static str {
         use std::str;
 
@@ -631,6 +1195,72 @@ impl VideoSubsystem {
         }
     }
 
+    /// Get the usable desktop area of the display at the index `display_index`, i.e. its bounds
+    /// minus the areas reserved by the OS such as task bars and menu bars.
+    pub fn display_usable_bounds(&self, display_index: i32) -> Result<Rect, Error> {
+        let mut out = mem::MaybeUninit::uninit();
+        let result = unsafe { sys::SDL_GetDisplayUsableBounds(display_index as c_int, out.as_mut_ptr()) == 0 };
+
+        if result {
+            let out = unsafe { out.assume_init() };
+            Ok(Rect::from_ll(out))
+        } else {
+            Err(get_error_as_error())
+        }
+    }
+
+    /// Get the orientation of the display at the index `display_index`.
+    ///
+    /// Returns `DisplayOrientation::Unknown` if the index is out of bounds or if the orientation
+    /// can't be determined.
+    pub fn display_orientation(&self, display_index: i32) -> DisplayOrientation {
+        let orientation = unsafe { sys::SDL_GetDisplayOrientation(display_index as c_int) };
+        DisplayOrientation::from_ll(orientation)
+    }
+
+    /// Get a handle to the display at the index `display_index`.
+    ///
+    /// Will return an error if the index is out of bounds.
+    pub fn display(&self, display_index: i32) -> Result<Display, Error> {
+        let num_displays = self.num_video_displays()?;
+        if display_index < 0 || display_index >= num_displays {
+            Err(Error::SdlError(format!(
+                "Display index {} is out of range (0..{})", display_index, num_displays)))
+        } else {
+            Ok(Display {
+                subsystem: self.clone(),
+                index: display_index,
+            })
+        }
+    }
+
+    /// Get handles to all the displays currently connected.
+    pub fn displays(&self) -> Result<Vec<Display>, Error> {
+        let num_displays = self.num_video_displays()?;
+        Ok((0..num_displays).map(|index| Display {
+            subsystem: self.clone(),
+            index,
+        }).collect())
+    }
+
+    /// Get the display whose bounds contain `point`, in desktop coordinates.
+    ///
+    /// Returns `Ok(None)` if the point lies outside of every display.
+    pub fn display_containing_point<P: Into<Point>>(&self, point: P) -> Result<Option<Display>, Error> {
+        let point = point.into();
+        for display in self.displays()? {
+            if display.bounds()?.contains_point(point) {
+                return Ok(Some(display));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the display that contains the center of `window`.
+    pub fn display_containing_window(&self, window: &Window) -> Result<Display, Error> {
+        window.display()
+    }
+
     pub fn num_display_modes(&self, display_index: i32) -> Result<i32, Error> {
         let result = unsafe { sys::SDL_GetNumDisplayModes(display_index as c_int) };
         if result < 0 {
@@ -1105,6 +1735,17 @@ impl Window {
         }
     }
 
+    /// Creates an OpenGL context sharing its objects (textures, buffers, ...) with `context`.
+    ///
+    /// `SDL_GL_SHARE_WITH_CURRENT_CONTEXT` is only set for this call; see `GLContextBuilder`
+    /// to request other attributes as well. The new context becomes the current one.
+    pub fn gl_create_shared_context(&self, context: &GLContext) -> Result<GLContext, Error> {
+        GLContextBuilder::new()
+            .share_with(context)
+            .build(self)
+            .map_err(|e| Error::SdlError(e.to_string()))
+    }
+
     /// Set the window's OpenGL context to the current context on the thread.
     pub fn gl_set_context_to_current(&self) -> Result<(), Error> {
         unsafe {
@@ -1159,6 +1800,27 @@ impl Window {
         }
     }
 
+    /// Get the native handles of the window and of its windowing subsystem.
+    ///
+    /// Returns an error if the video driver doesn't support `SDL_GetWindowWMInfo`.
+    pub fn wm_info(&self) -> Result<WindowManagerInfo, Error> {
+        unsafe {
+            let mut info: sys::SDL_SysWMinfo = mem::zeroed();
+            // SDL only fills in the fields known to the version given here.
+            info.version = sys::SDL_version {
+                major: sys::SDL_MAJOR_VERSION as u8,
+                minor: sys::SDL_MINOR_VERSION as u8,
+                patch: sys::SDL_PATCHLEVEL as u8,
+            };
+
+            if sys::SDL_GetWindowWMInfo(self.context.raw, &mut info) == sys::SDL_bool::SDL_FALSE {
+                Err(get_error_as_error())
+            } else {
+                Ok(WindowManagerInfo::from_ll(&info))
+            }
+        }
+    }
+
     pub fn display_index(&self) -> Result<i32, Error> {
         let result = unsafe { sys::SDL_GetWindowDisplayIndex(self.context.raw) };
         if result < 0 {
@@ -1168,6 +1830,15 @@ impl Window {
         }
     }
 
+    /// Get the display that contains the center of the window.
+    pub fn display(&self) -> Result<Display, Error> {
+        let index = self.display_index()?;
+        Ok(Display {
+            subsystem: self.subsystem().clone(),
+            index,
+        })
+    }
+
     pub fn set_display_mode<D>(&mut self, display_mode: D) -> Result<(), Error>
     where D: Into<Option<DisplayMode>>
     {
@@ -1215,6 +1886,12 @@ impl Window {
         }
     }
 
+    /// Returns true if this window was not created by SDL but adopted with
+    /// `VideoSubsystem::window_from_native`.
+    pub fn is_foreign(&self) -> bool {
+        self.window_flags() & sys::SDL_WindowFlags::SDL_WINDOW_FOREIGN as u32 != 0
+    }
+
     pub fn set_title(&mut self, title: &str) -> Result<(), NulError> {
         let title = CString::new(title)?;
         unsafe {
@@ -1493,6 +2170,47 @@ impl Window {
         }
     }
 
+    /// Sets the gamma ramp of the display the window is on; see `GammaRamp`.
+    pub fn apply_gamma_ramp(&mut self, ramp: &GammaRamp) -> Result<(), Error> {
+        self.set_gamma_ramp(&ramp.red, &ramp.green, &ramp.blue)
+    }
+
+    /// Gets the current gamma ramp of the display the window is on, as a `GammaRamp`.
+    pub fn current_gamma_ramp(&self) -> Result<GammaRamp, Error> {
+        let mut ramp = GammaRamp::identity();
+        let result = unsafe {
+            sys::SDL_GetWindowGammaRamp(
+                self.context.raw, ramp.red.as_mut_ptr(), ramp.green.as_mut_ptr(),
+                ramp.blue.as_mut_ptr()
+            )
+        };
+        if result == 0 {
+            Ok(ramp)
+        } else {
+            Err(get_error_as_error())
+        }
+    }
+
+    /// Saves the current gamma ramp, and returns a guard restoring it when dropped.
+    ///
+    /// ```no_run
+    /// # use sdl2::video::{GammaRamp, Window};
+    /// # fn fade(window: &mut Window) -> Result<(), sdl2::Error> {
+    /// let mut guard = window.gamma_ramp_guard()?;
+    /// let dark = GammaRamp::from_gamma(0.3)?;
+    /// for step in 0..=10 {
+    ///     let ramp = guard.original().lerp(&dark, step as f32 / 10.0);
+    ///     guard.apply_gamma_ramp(&ramp)?;
+    /// }
+    /// // the original ramp is restored here
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn gamma_ramp_guard(&mut self) -> Result<GammaRampGuard<'_>, Error> {
+        let original = self.current_gamma_ramp()?;
+        Ok(GammaRampGuard { window: self, original })
+    }
+
     /// Set the transparency of the window. The given value will be clamped internally between
     /// `0.0` (fully transparent), and `1.0` (fully opaque).
     ///
@@ -1522,6 +2240,172 @@ impl Window {
     }
 }
 
+/// A gamma ramp for the three color channels of a window, as used by `SDL_SetWindowGammaRamp`.
+///
+/// Each channel maps the 256 possible input intensities to a 16-bit output intensity.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GammaRamp {
+    pub red: [u16; 256],
+    pub green: [u16; 256],
+    pub blue: [u16; 256],
+}
+
+impl GammaRamp {
+    /// The identity ramp, which leaves colors unchanged.
+    pub fn identity() -> GammaRamp {
+        let mut channel = [0u16; 256];
+        for (i, value) in channel.iter_mut().enumerate() {
+            *value = (i as u16) << 8 | i as u16;
+        }
+        GammaRamp { red: channel, green: channel, blue: channel }
+    }
+
+    /// Computes the ramp of a gamma value with `SDL_CalculateGammaRamp`, for all three channels.
+    ///
+    /// A gamma of `1.0` gives the identity ramp, `0.0` a black ramp. Negative gammas are invalid.
+    pub fn from_gamma(gamma: f32) -> Result<GammaRamp, Error> {
+        if gamma.is_nan() || gamma < 0.0 {
+            return Err(Error::SdlError(format!("Invalid gamma value: {}", gamma)));
+        }
+        let mut channel = [0u16; 256];
+        unsafe { sys::SDL_CalculateGammaRamp(gamma, channel.as_mut_ptr()) };
+        Ok(GammaRamp { red: channel, green: channel, blue: channel })
+    }
+
+    /// Builds a ramp from one curve per channel.
+    ///
+    /// Each curve maps an input intensity in `[0.0, 1.0]` to an output intensity, which is
+    /// clamped to `[0.0, 1.0]`.
+    pub fn from_channels<R, G, B>(red: R, green: G, blue: B) -> GammaRamp
+    where R: Fn(f32) -> f32,
+          G: Fn(f32) -> f32,
+          B: Fn(f32) -> f32,
+    {
+        GammaRamp {
+            red: channel_from_curve(red),
+            green: channel_from_curve(green),
+            blue: channel_from_curve(blue),
+        }
+    }
+
+    /// Builds a ramp applying the same curve to the three channels.
+    ///
+    /// See `from_channels`.
+    pub fn from_curve<F: Fn(f32) -> f32>(curve: F) -> GammaRamp {
+        let channel = channel_from_curve(curve);
+        GammaRamp { red: channel, green: channel, blue: channel }
+    }
+
+    /// Linearly interpolates between `self` (at `t = 0.0`) and `other` (at `t = 1.0`).
+    ///
+    /// `t` is clamped to `[0.0, 1.0]`.
+    pub fn lerp(&self, other: &GammaRamp, t: f32) -> GammaRamp {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let lerp_channel = |from: &[u16; 256], to: &[u16; 256]| {
+            let mut channel = [0u16; 256];
+            for (i, value) in channel.iter_mut().enumerate() {
+                let (from, to) = (f32::from(from[i]), f32::from(to[i]));
+                *value = (from + (to - from) * t).round() as u16;
+            }
+            channel
+        };
+        GammaRamp {
+            red: lerp_channel(&self.red, &other.red),
+            green: lerp_channel(&self.green, &other.green),
+            blue: lerp_channel(&self.blue, &other.blue),
+        }
+    }
+}
+
+impl Default for GammaRamp {
+    fn default() -> GammaRamp {
+        GammaRamp::identity()
+    }
+}
+
+fn channel_from_curve<F: Fn(f32) -> f32>(curve: F) -> [u16; 256] {
+    let mut channel = [0u16; 256];
+    for (i, value) in channel.iter_mut().enumerate() {
+        let output = curve(i as f32 / 255.0);
+        let output = if output.is_nan() { 0.0 } else { output.clamp(0.0, 1.0) };
+        *value = (output * 65535.0).round() as u16;
+    }
+    channel
+}
+
+/// Restores the gamma ramp a window had when the guard was created, once dropped.
+///
+/// Obtained with `Window::gamma_ramp_guard()`; dereferences to the `Window`, so that ramps can be
+/// applied in the meantime, e.g. to fade the screen.
+pub struct GammaRampGuard<'a> {
+    window: &'a mut Window,
+    original: GammaRamp,
+}
+
+impl<'a> GammaRampGuard<'a> {
+    /// The ramp that will be restored.
+    pub fn original(&self) -> &GammaRamp {
+        &self.original
+    }
+}
+
+impl<'a> Deref for GammaRampGuard<'a> {
+    type Target = Window;
+
+    fn deref(&self) -> &Window {
+        self.window
+    }
+}
+
+impl<'a> DerefMut for GammaRampGuard<'a> {
+    fn deref_mut(&mut self) -> &mut Window {
+        self.window
+    }
+}
+
+impl<'a> Drop for GammaRampGuard<'a> {
+    fn drop(&mut self) {
+        // Errors cannot be reported from a destructor; the ramp was readable, so it should
+        // be writable as well.
+        let _ = self.window.apply_gamma_ramp(&self.original);
+    }
+}
+
+#[test]
+fn gamma_ramp_identity_curve() {
+    assert_eq!(GammaRamp::from_curve(|x| x), GammaRamp::identity());
+    let ramp = GammaRamp::identity();
+    assert_eq!(ramp.red[0], 0);
+    assert_eq!(ramp.red[128], 0x8080);
+    assert_eq!(ramp.blue[255], 0xffff);
+}
+
+#[test]
+fn gamma_ramp_channels_clamped() {
+    let ramp = GammaRamp::from_channels(|_| 2.0, |_| -1.0, |_| ::std::f32::NAN);
+    assert!(ramp.red.iter().all(|&v| v == 0xffff));
+    assert!(ramp.green.iter().all(|&v| v == 0));
+    assert!(ramp.blue.iter().all(|&v| v == 0));
+}
+
+#[test]
+fn gamma_ramp_lerp() {
+    let black = GammaRamp::from_curve(|_| 0.0);
+    let identity = GammaRamp::identity();
+    assert_eq!(black.lerp(&identity, 0.0), black);
+    assert_eq!(black.lerp(&identity, 1.0), identity);
+    assert_eq!(black.lerp(&identity, 4.0), identity);
+    let half = black.lerp(&identity, 0.5);
+    assert_eq!(half.green[255], 0x8000);
+    assert_eq!(half.green[0], 0);
+}
+
+#[test]
+fn gamma_ramp_invalid_gamma() {
+    assert!(GammaRamp::from_gamma(-1.0).is_err());
+    assert!(GammaRamp::from_gamma(::std::f32::NAN).is_err());
+}
+
 #[derive(Copy, Clone)]
 pub struct DriverIterator {
     length: i32,
@@ -1569,3 +2453,82 @@ pub fn drivers() -> DriverIterator {
         index: 0
     }
 }
+
+#[cfg(feature = "raw-window-handle")]
+mod raw_window_handle_impl {
+    use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+    use super::{Window, WindowManagerInfo};
+
+    /// The handle is obtained through `Window::wm_info()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `SDL_GetWindowWMInfo` fails or if SDL runs on a windowing subsystem that
+    /// `raw-window-handle` can't represent on the target platform.
+    unsafe impl HasRawWindowHandle for Window {
+        fn raw_window_handle(&self) -> RawWindowHandle {
+            let info = match self.wm_info() {
+                Ok(info) => info,
+                Err(e) => panic!("couldn't get the window manager info: {}", e),
+            };
+
+            match info {
+                #[cfg(target_os = "windows")]
+                WindowManagerInfo::Windows { window, hinstance, .. } => {
+                    use raw_window_handle::windows::WindowsHandle;
+                    RawWindowHandle::Windows(WindowsHandle {
+                        hwnd: window,
+                        hinstance,
+                        ..WindowsHandle::empty()
+                    })
+                }
+                #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+                          target_os = "netbsd", target_os = "openbsd"))]
+                WindowManagerInfo::X11 { display, window } => {
+                    use raw_window_handle::unix::XlibHandle;
+                    RawWindowHandle::Xlib(XlibHandle {
+                        window,
+                        display,
+                        ..XlibHandle::empty()
+                    })
+                }
+                #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd",
+                          target_os = "netbsd", target_os = "openbsd"))]
+                WindowManagerInfo::Wayland { display, surface, .. } => {
+                    use raw_window_handle::unix::WaylandHandle;
+                    RawWindowHandle::Wayland(WaylandHandle {
+                        surface,
+                        display,
+                        ..WaylandHandle::empty()
+                    })
+                }
+                #[cfg(target_os = "macos")]
+                WindowManagerInfo::Cocoa { window } => {
+                    use raw_window_handle::macos::MacOSHandle;
+                    // SDL doesn't expose the content view; consumers can get it from the window.
+                    RawWindowHandle::MacOS(MacOSHandle {
+                        ns_window: window,
+                        ..MacOSHandle::empty()
+                    })
+                }
+                #[cfg(target_os = "ios")]
+                WindowManagerInfo::UIKit { window } => {
+                    use raw_window_handle::ios::IOSHandle;
+                    RawWindowHandle::IOS(IOSHandle {
+                        ui_window: window,
+                        ..IOSHandle::empty()
+                    })
+                }
+                #[cfg(target_os = "android")]
+                WindowManagerInfo::Android { window, .. } => {
+                    use raw_window_handle::android::AndroidHandle;
+                    RawWindowHandle::Android(AndroidHandle {
+                        a_native_window: window,
+                        ..AndroidHandle::empty()
+                    })
+                }
+                other => panic!("unsupported windowing subsystem for raw-window-handle: {:?}", other),
+            }
+        }
+    }
+}
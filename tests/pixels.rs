@@ -0,0 +1,73 @@
+extern crate sdl2;
+
+use sdl2::pixels::{self, Color, ConvertPixelsError, Palette, PixelFormatEnum};
+use sdl2::surface::Surface;
+
+#[test]
+fn convert_pixels_round_trip() {
+    // two rows of two RGB24 pixels, with two bytes of padding
+    let rgb = [255, 0, 0, 0, 255, 0, 9, 9,
+               0, 0, 255, 10, 20, 30, 9, 9];
+    let (argb, pitch) = pixels::convert_pixels_to_vec(2, 2, PixelFormatEnum::RGB24, &rgb, 8,
+                                                      PixelFormatEnum::ARGB8888).unwrap();
+    assert_eq!(pitch, 8);
+    assert_eq!(argb.len(), 16);
+
+    let mut back = [0; 12];
+    pixels::convert_pixels(2, 2, PixelFormatEnum::ARGB8888, &argb, pitch,
+                           PixelFormatEnum::RGB24, &mut back, 6).unwrap();
+    assert_eq!(back, [255, 0, 0, 0, 255, 0, 0, 0, 255, 10, 20, 30]);
+
+    match pixels::convert_pixels(2, 2, PixelFormatEnum::ARGB8888, &argb, pitch,
+                                 PixelFormatEnum::RGB24, &mut back[..8], 6) {
+        Err(ConvertPixelsError::BufferTooSmall { buffer: "destination", .. }) => (),
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    // odd sizes have chroma planes rounded up to whole 2x2 blocks
+    let (yuv, pitch) = pixels::convert_pixels_to_vec(3, 3, PixelFormatEnum::RGB24, &[128; 27], 9,
+                                                     PixelFormatEnum::IYUV).unwrap();
+    assert_eq!(pitch, 3);
+    assert_eq!(yuv.len(), 17);
+}
+
+#[test]
+fn palette_colors_and_cycling() {
+    let mut palette = Palette::with_colors(&[Color::RGB(0, 0, 0), Color::RGB(1, 1, 1),
+                                             Color::RGB(2, 2, 2), Color::RGB(3, 3, 3)]).unwrap();
+    assert_eq!(palette.get(2), Some(Color::RGB(2, 2, 2)));
+    assert_eq!(palette.get(4), None);
+
+    palette.set_colors(2, &[Color::RGB(255, 0, 0), Color::RGB(0, 255, 0)]).unwrap();
+    assert_eq!(palette.colors()[2..], [Color::RGB(255, 0, 0), Color::RGB(0, 255, 0)]);
+    assert!(palette.set_colors(3, &[Color::RGB(0, 0, 0); 2]).is_err());
+
+    palette.cycle(1..4, 1).unwrap();
+    assert_eq!(palette.colors(), [Color::RGB(0, 0, 0), Color::RGB(0, 255, 0),
+                                  Color::RGB(1, 1, 1), Color::RGB(255, 0, 0)]);
+    assert!(palette.cycle(2..5, 1).is_err());
+
+    let mut surface = Surface::new(2, 2, PixelFormatEnum::Index8).unwrap();
+    surface.set_palette(&palette).unwrap();
+    let copy = surface.palette().unwrap();
+    assert_eq!(copy.get(3), Some(Color::RGB(255, 0, 0)));
+    assert!(Surface::new(2, 2, PixelFormatEnum::RGB24).unwrap().palette().is_none());
+}
+
+#[test]
+fn pixel_format_masks_match_sdl() {
+    let formats = [PixelFormatEnum::RGB332, PixelFormatEnum::RGB444, PixelFormatEnum::RGB555,
+                   PixelFormatEnum::BGR555, PixelFormatEnum::ARGB4444, PixelFormatEnum::RGBA4444,
+                   PixelFormatEnum::ABGR4444, PixelFormatEnum::BGRA4444, PixelFormatEnum::ARGB1555,
+                   PixelFormatEnum::RGBA5551, PixelFormatEnum::ABGR1555, PixelFormatEnum::BGRA5551,
+                   PixelFormatEnum::RGB565, PixelFormatEnum::BGR565, PixelFormatEnum::RGB24,
+                   PixelFormatEnum::BGR24, PixelFormatEnum::RGB888, PixelFormatEnum::RGBX8888,
+                   PixelFormatEnum::BGR888, PixelFormatEnum::BGRX8888, PixelFormatEnum::ARGB8888,
+                   PixelFormatEnum::RGBA8888, PixelFormatEnum::ABGR8888, PixelFormatEnum::BGRA8888,
+                   PixelFormatEnum::ARGB2101010];
+    for &format in &formats {
+        assert_eq!(format.masks(), Some(format.into_masks().unwrap()), "{:?}", format);
+    }
+    assert_eq!(PixelFormatEnum::RGB24.name(), "SDL_PIXELFORMAT_RGB24");
+    assert_eq!(PixelFormatEnum::YV12.name(), "SDL_PIXELFORMAT_YV12");
+}
@@ -0,0 +1,383 @@
+extern crate sdl2;
+
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::TextureAccess;
+use sdl2::surface::Surface;
+
+#[test]
+fn shared_texture_checks_renderer() {
+    let mut canvas_a = Surface::new(32, 32, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let mut canvas_b = Surface::new(32, 32, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+
+    let creator_a = canvas_a.texture_creator();
+    let texture = creator_a.create_shared_texture(None, TextureAccess::Static, 8, 8).unwrap();
+    let cache = vec![texture.clone()];
+    drop(creator_a);
+
+    assert!(canvas_b.copy(&texture, None, None).is_err());
+    assert!(canvas_a.copy(&cache[0], None, None).is_ok());
+
+    // the renderer outlives both its canvas and its texture creator
+    drop(canvas_a);
+    assert_eq!(cache[0].query().width, 8);
+    texture.set_alpha_mod(7);
+    assert_eq!(cache[0].alpha_mod(), 7);
+}
+
+#[test]
+fn shared_texture_update() {
+    use sdl2::rect::Rect;
+
+    let mut canvas = Surface::new(4, 4, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let creator = canvas.texture_creator();
+    let texture = creator.create_shared_texture(PixelFormatEnum::RGBA8888, TextureAccess::Streaming,
+                                                2, 1).unwrap();
+    let clone = texture.clone();
+
+    // opaque red and green, as native-endian RGBA8888 values
+    let pixels: Vec<u8> = [0xff00_00ffu32, 0x00ff_00ff].iter()
+        .flat_map(|pixel| pixel.to_ne_bytes().to_vec())
+        .collect();
+    texture.update(None, &pixels, 8).unwrap();
+    canvas.copy(&clone, None, Rect::new(0, 0, 2, 1)).unwrap();
+    assert_eq!(canvas.read_pixels(Rect::new(0, 0, 2, 1), PixelFormatEnum::RGBA8888).unwrap(),
+               pixels);
+
+    let blue = 0x0000_ffffu32.to_ne_bytes();
+    texture.with_lock(Rect::new(1, 0, 1, 1), |locked, _| {
+        assert!(clone.with_lock(None, |_, _| ()).is_err());
+        assert!(clone.update(None, &pixels, 8).is_err());
+        locked[..4].copy_from_slice(&blue);
+    }).unwrap();
+    canvas.copy(&clone, None, Rect::new(0, 0, 2, 1)).unwrap();
+    assert_eq!(canvas.read_pixels(Rect::new(1, 0, 1, 1), PixelFormatEnum::RGBA8888).unwrap(),
+               blue.to_vec());
+}
+
+#[test]
+fn atlas_build() {
+    use sdl2::render::atlas::AtlasBuilder;
+
+    let canvas = Surface::new(64, 64, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let creator = canvas.texture_creator();
+    let red = Surface::new(10, 12, PixelFormatEnum::RGBA8888).unwrap();
+    let blue = Surface::new(20, 6, PixelFormatEnum::RGB888).unwrap();
+
+    let atlas = AtlasBuilder::new(32, 32)
+        .padding(1)
+        .extrusion(1)
+        .add("red", &red)
+        .add("blue", &blue)
+        .build(&creator)
+        .unwrap();
+    let (_, red_rect) = atlas.region("red").unwrap();
+    assert_eq!(red_rect.size(), (10, 12));
+    let (blue_texture, blue_rect) = atlas.region("blue").unwrap();
+    assert_eq!(blue_rect.size(), (20, 6));
+    assert!(blue_texture.query().width >= 22);
+    assert!(atlas.region("green").is_none());
+}
+
+#[test]
+fn sprite_batch_flush() {
+    use sdl2::rect::Rect;
+    use sdl2::render::{BlendMode, SpriteBatch};
+
+    let mut canvas = Surface::new(64, 64, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let creator = canvas.texture_creator();
+    let a = creator.create_texture_static(None, 8, 8).unwrap();
+    let b = creator.create_texture_static(None, 8, 8).unwrap();
+
+    let mut batch = SpriteBatch::new();
+    for &grouping in &[false, true] {
+        batch.set_grouping(grouping);
+        for i in 0..10 {
+            let texture = if i % 2 == 0 { &a } else { &b };
+            batch.add(texture, None, Rect::new(i * 4, 0, 8, 8));
+        }
+        batch.add(&a, None, None).alpha_mod(128).blend_mode(BlendMode::Add);
+        let stats = batch.flush(&mut canvas).unwrap();
+        assert_eq!(stats.draw_calls, 11);
+        // the sprites are drawn in order, unless grouped by texture
+        assert_eq!(stats.texture_switches, if grouping { 2 } else { 11 });
+        assert_eq!(stats.state_changes, 2);
+        assert!(batch.is_empty());
+        // the state of the texture is restored
+        assert_eq!(a.alpha_mod(), 255);
+    }
+}
+
+#[test]
+fn nine_slice_and_tiled_copy() {
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
+    use sdl2::render::NineSliceInsets;
+
+    let mut canvas = Surface::new(64, 64, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let creator = canvas.texture_creator();
+    let mut tile = Surface::new(8, 8, PixelFormatEnum::RGBA8888).unwrap();
+    tile.fill_rect(None, Color::RGB(255, 0, 0)).unwrap();
+    let texture = creator.create_texture_from_surface(&tile).unwrap();
+
+    canvas.copy_nine_slice(&texture, None, NineSliceInsets::uniform(2), Rect::new(4, 4, 40, 20)).unwrap();
+    assert!(canvas.copy_nine_slice(&texture, None, NineSliceInsets::uniform(5), Rect::new(0, 0, 8, 8)).is_err());
+
+    canvas.set_clip_rect(Rect::new(0, 0, 16, 16));
+    canvas.copy_tiled(&texture, None, Rect::new(0, 0, 64, 64), (3, 3)).unwrap();
+    canvas.set_clip_rect(None);
+
+    let pixels = canvas.read_pixels(Rect::new(20, 20, 1, 1), PixelFormatEnum::RGBA8888).unwrap();
+    assert_eq!(pixels, vec![255, 0, 0, 255]);
+}
+
+#[test]
+fn read_surface_and_screenshot() {
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
+
+    let mut canvas = Surface::new(16, 8, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    canvas.set_draw_color(Color::RGB(0, 0, 255));
+    canvas.clear();
+
+    let surface = canvas.read_surface(None, PixelFormatEnum::RGB24).unwrap();
+    assert_eq!(surface.size(), (16, 8));
+    assert_eq!(surface.pixel_format_enum(), PixelFormatEnum::RGB24);
+    surface.with_lock(|pixels| assert_eq!(&pixels[0..3], &[0, 0, 255]));
+
+    let part = canvas.read_surface(Rect::new(2, 2, 4, 4), PixelFormatEnum::ARGB8888).unwrap();
+    assert_eq!(part.size(), (4, 4));
+
+    // unique per process, since tests may run concurrently
+    let name = format!("rust-sdl2-screenshot-test-{}", std::process::id());
+    let path = std::env::temp_dir().join(format!("{}.bmp", name));
+    canvas.screenshot(&path).unwrap();
+    let loaded = Surface::load_bmp(&path).unwrap();
+    assert_eq!(loaded.size(), (16, 8));
+    std::fs::remove_file(&path).unwrap();
+
+    // unknown formats are rejected instead of being written as BMP
+    for unknown in &[format!("{}.jpg", name), name.clone()] {
+        let path = std::env::temp_dir().join(unknown);
+        assert!(canvas.screenshot(&path).is_err());
+        assert!(!path.exists());
+    }
+}
+
+#[test]
+fn golden_compare() {
+    use sdl2::golden::{self, CompareError};
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
+
+    let mut canvas = Surface::new(16, 16, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    canvas.set_draw_color(Color::RGB(0, 0, 0));
+    canvas.clear();
+    let expected = canvas.read_surface(None, PixelFormatEnum::RGB24).unwrap();
+
+    canvas.set_draw_color(Color::RGB(255, 255, 255));
+    canvas.fill_rect(Rect::new(0, 0, 2, 2)).unwrap();
+    let actual = canvas.read_surface(None, PixelFormatEnum::ARGB8888).unwrap();
+
+    assert!(golden::compare(&expected, &expected, 0).unwrap().is_match());
+    let comparison = golden::compare(&expected, &actual, 8).unwrap();
+    assert_eq!(comparison.mismatched_pixels(), 4);
+    assert_eq!(comparison.total_pixels(), 256);
+    assert_eq!(comparison.diff().size(), (16, 16));
+    assert!(comparison.psnr().is_finite());
+
+    let small = Surface::new(8, 8, PixelFormatEnum::RGB24).unwrap();
+    match golden::compare(&expected, &small, 0) {
+        Err(CompareError::SizeMismatch { expected, actual }) => {
+            assert_eq!((expected, actual), ((16, 16), (8, 8)));
+        },
+        _ => panic!("expected a size mismatch"),
+    }
+}
+
+#[test]
+fn render_target_stack() {
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
+
+    let mut canvas = Surface::new(32, 32, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let creator = canvas.texture_creator();
+    let mut outer = creator.create_texture_target(None, 16, 16).unwrap();
+    let mut inner = creator.create_texture_target(None, 8, 8).unwrap();
+
+    canvas.set_draw_color(Color::RGB(1, 2, 3));
+    canvas.set_viewport(Rect::new(4, 4, 20, 20));
+    {
+        let mut outer_canvas = canvas.push_target(&mut outer).unwrap();
+        assert_eq!(outer_canvas.viewport(), Rect::new(0, 0, 16, 16));
+        outer_canvas.set_draw_color(Color::RGB(255, 0, 0));
+        outer_canvas.set_clip_rect(Rect::new(0, 0, 4, 4));
+        {
+            let mut inner_canvas = outer_canvas.push_target(&mut inner).unwrap();
+            inner_canvas.set_draw_color(Color::RGB(0, 255, 0));
+            inner_canvas.set_scale(2.0, 2.0).unwrap();
+            inner_canvas.clear();
+            inner_canvas.pop().unwrap();
+        }
+        assert_eq!(outer_canvas.draw_color(), Color::RGB(255, 0, 0));
+        assert_eq!(outer_canvas.clip_rect(), Some(Rect::new(0, 0, 4, 4)));
+        assert_eq!(outer_canvas.scale(), (1.0, 1.0));
+        outer_canvas.copy(&inner, None, None).unwrap();
+    }
+    assert_eq!(canvas.draw_color(), Color::RGB(1, 2, 3));
+    assert_eq!(canvas.viewport(), Rect::new(4, 4, 20, 20));
+    assert_eq!(canvas.clip_rect(), None);
+
+    {
+        let mut state = canvas.push_state();
+        state.set_draw_color(Color::RGB(9, 9, 9));
+    }
+    assert_eq!(canvas.draw_color(), Color::RGB(1, 2, 3));
+    canvas.copy(&outer, None, None).unwrap();
+}
+
+#[test]
+fn transformed_canvas_draws_through_camera() {
+    use sdl2::pixels::Color;
+    use sdl2::rect::Rect;
+    use sdl2::render::{Camera, TransformedCanvas};
+
+    let mut canvas = Surface::new(32, 32, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    canvas.set_draw_color(Color::RGB(0, 0, 0));
+    canvas.clear();
+    canvas.set_draw_color(Color::RGB(255, 255, 255));
+
+    let mut camera = Camera::centered((100.0, 100.0), (32, 32));
+    camera.zoom = 2.0;
+    {
+        let mut world = TransformedCanvas::new(&mut canvas, camera);
+        assert_eq!(world.visible_world_rect().unwrap(), Rect::new(92, 92, 16, 16));
+        assert!(world.is_visible(Rect::new(100, 100, 1, 1)));
+        assert!(!world.is_visible(Rect::new(200, 200, 4, 4)));
+        // covers the screen rectangle (16, 16, 4, 4)
+        world.fill_rect(Rect::new(100, 100, 2, 2)).unwrap();
+        world.fill_rect(Rect::new(500, 500, 2, 2)).unwrap();
+
+        world.camera_mut().rotation = 30.0;
+        world.fill_rect(Rect::new(95, 95, 2, 2)).unwrap();
+        world.draw_rect(Rect::new(95, 95, 2, 2)).unwrap();
+        world.draw_line((90, 90), (110, 110)).unwrap();
+    }
+
+    let pixel = |canvas: &sdl2::render::Canvas<Surface>, x, y| {
+        canvas.read_pixels(Rect::new(x, y, 1, 1), PixelFormatEnum::RGBA8888).unwrap()
+    };
+    assert_eq!(pixel(&canvas, 17, 17), vec![255, 255, 255, 255]);
+    assert_eq!(pixel(&canvas, 20, 17), vec![255, 0, 0, 0]);
+
+    // huge zooms are clamped to the range of a `Rect`, and invalid zooms are rejected
+    let mut world = TransformedCanvas::new(&mut canvas, Camera { zoom: 1e12, ..camera });
+    world.fill_rect(Rect::new(100, 100, 2, 2)).unwrap();
+    world.draw_rect(Rect::new(90, 90, 20, 20)).unwrap();
+    assert_eq!(world.visible_world_rect().unwrap(), Rect::new(99, 99, 2, 2));
+    world.camera_mut().zoom = 0.0;
+    assert!(world.fill_rect(Rect::new(100, 100, 2, 2)).is_err());
+    assert!(world.visible_world_rect().is_err());
+}
+
+#[test]
+fn typed_texture_lock() {
+    use sdl2::pixels::Color;
+
+    let canvas = Surface::new(16, 16, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let creator = canvas.texture_creator();
+    let mut texture = creator.create_texture_streaming(PixelFormatEnum::ARGB8888, 5, 3).unwrap();
+
+    texture.with_lock_typed::<u32, _, _, _>(None, |lock| {
+        assert_eq!((lock.width(), lock.height()), (5, 3));
+        for (y, row) in lock.rows_mut().enumerate() {
+            assert_eq!(row.len(), 5);
+            for pixel in row.iter_mut() {
+                *pixel = y as u32;
+            }
+        }
+        lock.put_pixel(4, 2, Color::RGBA(1, 2, 3, 4));
+        assert_eq!(lock.row(2)[4], 0x0401_0203);
+        assert_eq!(lock.rows().map(|row| row[0]).collect::<Vec<_>>(), vec![0, 1, 2]);
+    }).unwrap();
+
+    assert!(texture.with_lock_typed::<u16, _, _, _>(None, |_| ()).is_err());
+
+    let mut rgb = creator.create_texture_streaming(PixelFormatEnum::RGB24, 2, 2).unwrap();
+    rgb.with_lock_typed::<[u8; 3], _, _, _>(None, |lock| {
+        lock.put_pixel(1, 1, Color::RGB(10, 20, 30));
+        assert_eq!(lock.row(1)[1], [10, 20, 30]);
+    }).unwrap();
+}
+
+#[test]
+fn nv12_texture_update() {
+    use sdl2::pixels::{self, YuvConversionMode};
+    use sdl2::rect::Rect;
+    use sdl2::render::UpdateTextureYUVError;
+
+    let canvas = Surface::new(16, 16, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    let creator = canvas.texture_creator();
+    let mut texture = creator.create_texture_streaming(PixelFormatEnum::NV12, 8, 4).unwrap();
+    assert_eq!(texture.query().format, PixelFormatEnum::NV12);
+
+    let y_plane = [16u8; 10 * 4];
+    let uv_plane = [128u8; 10 * 2];
+    texture.update_nv(None, &y_plane, 10, &uv_plane, 10).unwrap();
+    texture.update_nv(Rect::new(2, 2, 2, 2), &y_plane[..4], 2, &uv_plane[..2], 2).unwrap();
+
+    match texture.update_nv(None, &y_plane, 10, &uv_plane[..10], 10) {
+        Err(UpdateTextureYUVError::InvalidPlaneLength { plane: "uv", .. }) => (),
+        result => panic!("unexpected result {:?}", result),
+    }
+    match texture.update_nv(Rect::new(1, 0, 2, 2), &y_plane[..4], 2, &uv_plane[..2], 2) {
+        Err(UpdateTextureYUVError::XMustBeMultipleOfTwoForFormat(1)) => (),
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    // odd sizes round the UV plane up to whole 2x2 blocks
+    let mut odd = creator.create_texture_streaming(PixelFormatEnum::NV12, 5, 3).unwrap();
+    odd.update_nv(None, &y_plane[..15], 5, &uv_plane[..12], 6).unwrap();
+    match odd.update_nv(None, &y_plane[..15], 5, &uv_plane[..10], 5) {
+        Err(UpdateTextureYUVError::SdlError(_)) => (),
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    let mut rgb = creator.create_texture_streaming(PixelFormatEnum::RGB24, 8, 4).unwrap();
+    assert!(rgb.update_nv(None, &y_plane, 10, &uv_plane, 10).is_err());
+
+    pixels::set_yuv_conversion_mode(YuvConversionMode::Bt709);
+    assert_eq!(pixels::yuv_conversion_mode(), YuvConversionMode::Bt709);
+    pixels::set_yuv_conversion_mode(YuvConversionMode::Automatic);
+    assert_eq!(pixels::yuv_conversion_mode_for_resolution(1920, 1080), YuvConversionMode::Bt709);
+}
+
+#[test]
+fn float_drawing_rounds_to_pixels() {
+    use sdl2::pixels::Color;
+    use sdl2::rect::{FPoint, FRect};
+
+    let mut canvas = Surface::new(8, 8, PixelFormatEnum::RGBA8888).unwrap().into_canvas().unwrap();
+    canvas.set_draw_color(Color::RGB(0, 0, 0));
+    canvas.clear();
+    canvas.set_draw_color(Color::RGB(255, 255, 255));
+    // the edges round to 1 and 3, so this covers the pixels (1, 1) to (2, 2)
+    canvas.fill_frect(FRect::new(0.6, 0.6, 2.0, 2.0)).unwrap();
+    // the bottom edge at 4.4 rounds to 4, so this only covers the row y = 3
+    canvas.fill_frect(FRect::new(4.6, 2.6, 2.0, 1.8)).unwrap();
+    // empty once rounded
+    canvas.fill_frect(FRect::new(5.1, 5.1, 0.2, 0.2)).unwrap();
+    canvas.draw_fpoint(FPoint::new(6.4, 6.6)).unwrap();
+    canvas.draw_fline((0.2, 5.4), (3.4, 4.6)).unwrap();
+
+    let surface = canvas.surface();
+    let white = Some(Color::RGB(255, 255, 255));
+    assert_eq!(surface.get_pixel(1, 1), white);
+    assert_eq!(surface.get_pixel(2, 2), white);
+    assert_eq!(surface.get_pixel(3, 2), Some(Color::RGB(0, 0, 0)));
+    assert_eq!(surface.get_pixel(5, 3), white);
+    assert_eq!(surface.get_pixel(5, 4), Some(Color::RGB(0, 0, 0)));
+    assert_eq!(surface.get_pixel(5, 5), Some(Color::RGB(0, 0, 0)));
+    assert_eq!(surface.get_pixel(6, 7), white);
+    assert_eq!(surface.get_pixel(0, 5), white);
+    assert_eq!(surface.get_pixel(3, 5), white);
+}
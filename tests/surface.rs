@@ -0,0 +1,114 @@
+extern crate sdl2;
+
+use sdl2::pixels::{Color, Palette, PixelFormatEnum};
+use sdl2::rect::Rect;
+use sdl2::surface::Surface;
+
+#[test]
+fn surface_from_format() {
+    for &format in &[PixelFormatEnum::Index8, PixelFormatEnum::Index1MSB,
+                     PixelFormatEnum::RGB24, PixelFormatEnum::ARGB8888] {
+        let surface = Surface::new(9, 3, format).unwrap();
+        assert_eq!(surface.pixel_format_enum(), format);
+        assert_eq!(surface.size(), (9, 3));
+    }
+
+    let mut data = vec![0; 12 * 2];
+    let surface = Surface::from_data(&mut data, 4, 2, 12, PixelFormatEnum::RGB24).unwrap();
+    assert_eq!(surface.pitch(), 12);
+    let mut short = vec![0; 12];
+    assert!(Surface::from_data(&mut short, 4, 2, 12, PixelFormatEnum::RGB24).is_err());
+    // rows would overlap with a pitch smaller than a row of pixels
+    let mut overlapping = vec![0; 12 * 2];
+    assert!(Surface::from_data(&mut overlapping, 4, 2, 8, PixelFormatEnum::RGB24).is_err());
+    assert!(Surface::from_data(&mut overlapping, 4, 1, 0, PixelFormatEnum::RGB24).is_err());
+}
+
+#[test]
+fn surface_pixel_access() {
+    let mut surface = Surface::new(3, 2, PixelFormatEnum::RGB24).unwrap();
+    surface.put_pixel(2, 1, Color::RGB(10, 20, 30)).unwrap();
+    assert_eq!(surface.get_pixel(2, 1), Some(Color::RGB(10, 20, 30)));
+    assert_eq!(surface.get_pixel(0, 0), Some(Color::RGB(0, 0, 0)));
+    assert_eq!(surface.get_pixel(3, 0), None);
+    assert!(surface.put_pixel(0, 2, Color::RGB(0, 0, 0)).is_err());
+
+    let pixels: Vec<_> = surface.pixels_iter().collect();
+    assert_eq!(pixels.len(), 6);
+    assert_eq!(pixels[5], (2, 1, Color::RGB(10, 20, 30)));
+    assert_eq!(pixels[3].0, 0);
+
+    // SDL's default 1-bit palette starts with white, so set one where white is index 1
+    let (black, white) = (Color::RGB(0, 0, 0), Color::RGB(255, 255, 255));
+    let mut bitmap = Surface::new(10, 1, PixelFormatEnum::Index1MSB).unwrap();
+    bitmap.set_palette(&Palette::with_colors(&[black, white]).unwrap()).unwrap();
+    bitmap.put_pixel(9, 0, white).unwrap();
+    assert_eq!(bitmap.get_pixel(9, 0), Some(white));
+    assert_eq!(bitmap.get_pixel(8, 0), Some(black));
+    bitmap.with_lock(|pixels| assert_eq!(pixels[..2], [0x00, 0x40]));
+}
+
+#[test]
+fn surface_transforms() {
+    let mut surface = Surface::new(3, 2, PixelFormatEnum::RGB24).unwrap();
+    let red = Color::RGB(255, 0, 0);
+    let blue = Color::RGB(0, 0, 255);
+    surface.put_pixel(0, 0, red).unwrap();
+    surface.put_pixel(2, 1, blue).unwrap();
+
+    let copy = surface.duplicate().unwrap();
+    assert_eq!(copy.get_pixel(0, 0), Some(red));
+
+    let flipped = surface.flip_horizontal().unwrap();
+    assert_eq!(flipped.get_pixel(2, 0), Some(red));
+    assert_eq!(flipped.get_pixel(0, 1), Some(blue));
+
+    let flipped = surface.flip_vertical().unwrap();
+    assert_eq!(flipped.get_pixel(0, 1), Some(red));
+    assert_eq!(flipped.get_pixel(2, 0), Some(blue));
+
+    let rotated = surface.rotate90(1).unwrap();
+    assert_eq!(rotated.size(), (2, 3));
+    assert_eq!(rotated.get_pixel(1, 0), Some(red));
+    assert_eq!(rotated.get_pixel(0, 2), Some(blue));
+    let rotated = surface.rotate90(-1).unwrap();
+    assert_eq!(rotated.get_pixel(0, 2), Some(red));
+    assert_eq!(rotated.get_pixel(1, 0), Some(blue));
+
+    let sub = surface.sub_surface(Rect::new(1, 1, 2, 1)).unwrap();
+    assert_eq!(sub.size(), (2, 1));
+    assert_eq!(sub.get_pixel(1, 0), Some(blue));
+    assert!(surface.sub_surface(Rect::new(2, 0, 2, 1)).is_err());
+
+    let mut stretched = Surface::new(6, 4, PixelFormatEnum::RGB24).unwrap();
+    surface.soft_stretch(None, &mut stretched, None).unwrap();
+    assert_eq!(stretched.get_pixel(1, 1), Some(red));
+    assert_eq!(stretched.get_pixel(5, 3), Some(blue));
+
+    let bitmap = Surface::new(8, 1, PixelFormatEnum::Index1MSB).unwrap();
+    assert!(bitmap.flip_horizontal().is_err());
+}
+
+#[test]
+fn surface_view() {
+    // SDL fills the default 8-bit palette with white, so set one containing red
+    let mut sheet = Surface::new(4, 4, PixelFormatEnum::Index8).unwrap();
+    let red = Color::RGB(255, 0, 0);
+    sheet.set_palette(&Palette::with_colors(&[Color::RGB(0, 0, 0), red]).unwrap()).unwrap();
+    sheet.put_pixel(3, 3, red).unwrap();
+    let sheet_colors = sheet.palette().unwrap().colors();
+    {
+        let mut view = sheet.view(Rect::new(2, 2, 2, 2)).unwrap();
+        assert_eq!(view.size(), (2, 2));
+        assert_eq!(view.pitch(), 4);
+        assert_eq!(view.palette().unwrap().colors(), sheet_colors);
+        assert_eq!(view.get_pixel(1, 1), Some(red));
+        view.put_pixel(0, 0, red).unwrap();
+    }
+    assert_eq!(sheet.get_pixel(2, 2), Some(red));
+    assert!(sheet.view(Rect::new(3, 3, 2, 1)).is_err());
+
+    let mut bitmap = Surface::new(16, 2, PixelFormatEnum::Index1MSB).unwrap();
+    assert!(bitmap.view(Rect::new(8, 1, 8, 1)).is_ok());
+    assert!(bitmap.view(Rect::new(4, 0, 8, 1)).is_err());
+}
@@ -11,3 +11,47 @@ fn display_name_no_segfault() {
     } // in Err(), environment has no video device (for instance travis)
     // so ignore it
 }
+
+#[test]
+fn display_out_of_range() {
+    let sdl_context = sdl2::init().unwrap();
+    let video_subsystem = sdl_context.video();
+    if let Ok(video_subsystem) = video_subsystem {
+        assert!(video_subsystem.display(99).is_err());
+        assert!(video_subsystem.display(-1).is_err());
+        for display in video_subsystem.displays().unwrap() {
+            let bounds = display.bounds().unwrap();
+            let found = video_subsystem.display_containing_point(bounds.center()).unwrap();
+            assert!(found.is_some());
+        }
+    } // in Err(), environment has no video device
+}
+
+#[test]
+fn gl_shared_context() {
+    let sdl_context = sdl2::init().unwrap();
+    let video_subsystem = sdl_context.video();
+    if let Ok(video_subsystem) = video_subsystem {
+        let window = video_subsystem.window("gl_shared_context", 64, 64)
+            .opengl()
+            .hidden()
+            .build();
+        // no OpenGL available (not even Mesa's software renderer): nothing to test
+        if let Ok(window) = window {
+            let context = window.gl_create_context().unwrap();
+            let shared = window.gl_create_shared_context(&context).unwrap();
+            assert!(shared.is_current());
+            assert!(!video_subsystem.gl_attr().share_with_current_context());
+
+            let mismatch = sdl2::video::GLContextBuilder::new()
+                .min_stencil_size(255)
+                .build(&window);
+            match mismatch {
+                Err(sdl2::video::GLContextBuildError::AttributeMismatch { requested, .. }) => {
+                    assert_eq!(requested, 255)
+                },
+                _ => panic!("expected an attribute mismatch"),
+            }
+        }
+    } // in Err(), environment has no video device
+}